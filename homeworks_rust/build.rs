@@ -0,0 +1,76 @@
+// Embeds the original version of every exercise under homeworks/ into the
+// binary, so that `rustlings reset` can restore them after students edit them.
+// The originals are read from the commit checked out in git, never from the
+// working tree, which holds the students' own versions. Without git, none
+// are embedded and reset falls back to the `.orig` copies next to exercises.
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// Run git in the crate directory, returning its output if it succeeded
+fn git(args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if output.status.success() {
+        Some(output.stdout)
+    } else {
+        None
+    }
+}
+
+fn git_string(args: &[&str]) -> Option<String> {
+    git(args).map(|stdout| String::from_utf8_lossy(&stdout).trim().to_string())
+}
+
+fn collect_exercises(exercises: &mut Vec<(String, String)>) {
+    let listing = match git(&["ls-tree", "-r", "-z", "--name-only", "HEAD", "homeworks"]) {
+        Some(listing) => listing,
+        None => return,
+    };
+    for path in String::from_utf8_lossy(&listing).split('\0') {
+        if !path.ends_with(".rs") {
+            continue;
+        }
+        if let Some(source) = git(&["show", &format!("HEAD:./{}", path)]) {
+            exercises.push((path.to_string(), String::from_utf8_lossy(&source).to_string()));
+        }
+    }
+}
+
+// Embed the originals again whenever another commit is checked out
+fn rerun_on_new_commit() {
+    if let Some(head) = git_string(&["rev-parse", "--git-path", "HEAD"]) {
+        println!("cargo:rerun-if-changed={}", head);
+    }
+    if let Some(branch) = git_string(&["symbolic-ref", "-q", "HEAD"]) {
+        if let Some(branch) = git_string(&["rev-parse", "--git-path", &branch]) {
+            println!("cargo:rerun-if-changed={}", branch);
+        }
+    }
+    if let Some(packed_refs) = git_string(&["rev-parse", "--git-path", "packed-refs"]) {
+        println!("cargo:rerun-if-changed={}", packed_refs);
+    }
+}
+
+fn main() {
+    rerun_on_new_commit();
+
+    let mut exercises = Vec::new();
+    collect_exercises(&mut exercises);
+    exercises.sort();
+
+    // Lets the tests that need the originals skip themselves without them
+    println!("cargo:rustc-check-cfg=cfg(embedded_exercises)");
+    if !exercises.is_empty() {
+        println!("cargo:rustc-cfg=embedded_exercises");
+    }
+
+    let mut generated = String::from("pub const ORIGINAL_EXERCISES: &[(&str, &str)] = &[\n");
+    for (path, source) in exercises {
+        generated.push_str(&format!("    ({:?}, {:?}),\n", path, source));
+    }
+    generated.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("original_exercises.rs"), generated).unwrap();
+}
@@ -0,0 +1,221 @@
+use crate::diagnostics::CompileError;
+use crate::exercise::{CompiledExercise, Exercise, ExerciseOutput, Mode};
+use crate::progress::hex;
+use crate::verify::Outcome;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+const CACHE_DIR_PATH: &str = ".rustlings-cache";
+const BINARY_FILE_NAME: &str = "binary";
+const RESULT_FILE_NAME: &str = "result.toml";
+// Bumped whenever what gets cached changes, so older entries are not reused
+const CACHE_FORMAT_VERSION: &str = "4";
+
+// A directory of compiled exercises and the results of running them,
+// keyed by a hash of everything that influences the build
+pub struct Cache {
+    dir: PathBuf,
+    // The output of `rustc --version --verbose`
+    rustc_version: String,
+}
+
+// The cached build of one version of an exercise
+pub struct CacheEntry {
+    dir: PathBuf,
+}
+
+// The recorded result of running a cached build
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct CachedResult {
+    pub outcome: Outcome,
+    pub stdout: String,
+    pub stderr: String,
+    #[serde(default)]
+    pub errors: Vec<CompileError>,
+}
+
+impl Cache {
+    // Open the cache of the current directory.
+    // There is no cache if the version of rustc can't be determined.
+    pub fn open() -> Option<Cache> {
+        let output = Command::new("rustc").args(["--version", "--verbose"]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(Cache {
+            dir: PathBuf::from(CACHE_DIR_PATH),
+            rustc_version: String::from_utf8_lossy(&output.stdout).to_string(),
+        })
+    }
+
+    // The entry for the current source of the exercise.
+    // Only binaries and test harnesses of single files are cached.
+    pub fn entry(&self, exercise: &Exercise) -> Option<CacheEntry> {
+        if let Mode::Clippy | Mode::Cargo = exercise.mode {
+            return None;
+        }
+        let source = fs::read(&exercise.path).ok()?;
+        let rustc_args = exercise.build.rustc_args();
+        let mut hasher = Sha256::new();
+        hash_field(&mut hasher, CACHE_FORMAT_VERSION);
+        hash_field(&mut hasher, &self.rustc_version);
+        hash_field(&mut hasher, exercise.mode.to_string());
+        hash_field(&mut hasher, source);
+        hash_field(&mut hasher, exercise.hidden_tests_source());
+        hash_field(&mut hasher, exercise.stdin().unwrap_or_default());
+        hash_field(&mut hasher, rustc_args.len().to_string());
+        for arg in &rustc_args {
+            hash_field(&mut hasher, arg);
+        }
+        // The cached outcome depends on what the output is checked against,
+        // and on the limits the binary ran under
+        hash_field(&mut hasher, format!("{:?}", exercise.expected_output()));
+        hash_field(&mut hasher, format!("{:?}", exercise.limits));
+        let key = hex(&hasher.finalize());
+        Some(CacheEntry {
+            dir: self.dir.join(key),
+        })
+    }
+
+    // Remove every cached build
+    pub fn clean() -> io::Result<()> {
+        match fs::remove_dir_all(CACHE_DIR_PATH) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+// Add a field to the key with its length in front, so that the
+// boundaries between fields are part of the key too
+fn hash_field(hasher: &mut Sha256, field: impl AsRef<[u8]>) {
+    let field = field.as_ref();
+    hasher.update((field.len() as u64).to_le_bytes());
+    hasher.update(field);
+}
+
+impl CacheEntry {
+    // The cached binary, if this version of the exercise compiled before
+    pub fn binary(&self) -> Option<PathBuf> {
+        Some(self.dir.join(BINARY_FILE_NAME)).filter(|path| path.exists())
+    }
+
+    // The recorded result, if this version of the exercise was run before
+    pub fn result(&self) -> Option<CachedResult> {
+        let toml_str = fs::read_to_string(self.dir.join(RESULT_FILE_NAME)).ok()?;
+        toml::from_str(&toml_str).ok()
+    }
+
+    // Keep a copy of the binary of a successful compilation
+    pub fn store_binary(&self, compilation: &CompiledExercise) {
+        let _ignored = fs::create_dir_all(&self.dir).and_then(|_| {
+            // Copy to a temporary name first so a partial copy is never used
+            let partial = self.dir.join(format!("{}.partial", BINARY_FILE_NAME));
+            compilation.save_binary(&partial)?;
+            fs::rename(&partial, self.dir.join(BINARY_FILE_NAME))
+        });
+    }
+
+    // Record the result of compiling and running this version of the exercise
+    pub fn store_result(&self, outcome: Outcome, output: &ExerciseOutput) {
+        let result = CachedResult {
+            outcome,
+            stdout: output.stdout.clone(),
+            stderr: output.stderr.clone(),
+            errors: output.errors.clone(),
+        };
+        if let Ok(toml_str) = toml::to_string(&result) {
+            let _ignored = fs::create_dir_all(&self.dir)
+                .and_then(|_| fs::write(self.dir.join(RESULT_FILE_NAME), toml_str));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    fn cache() -> Cache {
+        Cache {
+            dir: env::temp_dir().join(format!("rustlings-cache-test-{}", std::process::id())),
+            rustc_version: String::from("rustc 1.0.0"),
+        }
+    }
+
+    fn exercise(mode: Mode) -> Exercise {
+        Exercise {
+            name: String::from("compSuccess"),
+            path: PathBuf::from("tests/fixture/success/compSuccess.rs"),
+            mode,
+            ..Default::default()
+        }
+    }
+
+    fn error() -> CompileError {
+        CompileError {
+            code: String::from("E0384"),
+            location: Some(String::from("compSuccess.rs:8:5")),
+            source_line: None,
+        }
+    }
+
+    #[test]
+    fn test_entry_depends_on_mode() {
+        let cache = cache();
+        let compile_entry = cache.entry(&exercise(Mode::Compile)).unwrap();
+        let test_entry = cache.entry(&exercise(Mode::Test)).unwrap();
+        assert_ne!(compile_entry.dir, test_entry.dir);
+        assert!(cache.entry(&exercise(Mode::Clippy)).is_none());
+    }
+
+    #[test]
+    fn test_entry_keeps_rustc_args_apart() {
+        let cache = cache();
+        let mut spaced = exercise(Mode::Compile);
+        spaced.build.rustc_flags = Some(vec![String::from("-C"), String::from("a b")]);
+        let mut split = exercise(Mode::Compile);
+        split.build.rustc_flags = Some(vec![String::from("-C a"), String::from("b")]);
+        assert_ne!(cache.entry(&spaced).unwrap().dir, cache.entry(&split).unwrap().dir);
+    }
+
+    #[test]
+    fn test_entry_depends_on_limits() {
+        let cache = cache();
+        let mut limited = exercise(Mode::Compile);
+        limited.limits.timeout_secs = Some(1);
+        let entry = cache.entry(&exercise(Mode::Compile)).unwrap();
+        assert_ne!(entry.dir, cache.entry(&limited).unwrap().dir);
+    }
+
+    #[test]
+    fn test_result_roundtrip() {
+        let cache = cache();
+        let entry = cache.entry(&exercise(Mode::Compile)).unwrap();
+        assert!(entry.result().is_none());
+
+        let output = ExerciseOutput {
+            stdout: String::from("out\n"),
+            stderr: String::new(),
+            stopped: None,
+            errors: vec![error()],
+        };
+        entry.store_result(Outcome::RunFailed, &output);
+        let result = entry.result().unwrap();
+        fs::remove_dir_all(&cache.dir).unwrap();
+
+        assert_eq!(
+            result,
+            CachedResult {
+                outcome: Outcome::RunFailed,
+                stdout: String::from("out\n"),
+                stderr: String::new(),
+                errors: vec![error()],
+            }
+        );
+    }
+}
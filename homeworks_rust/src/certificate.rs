@@ -0,0 +1,213 @@
+use crate::exercise::Exercise;
+use crate::progress::{now, source_hash};
+use crate::verify::{evaluate, RunMode};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Signed before the claim, so that a certificate signature can't be
+// mistaken for the signature of anything else made with the same keypair
+const SIGNING_CONTEXT: &[u8] = b"rustlings completion certificate v1\n";
+
+// An exercise as it was when it was verified
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CertifiedExercise {
+    pub name: String,
+    // The SHA-256 of the source files of the exercise
+    pub sha256: String,
+}
+
+// What the student signs: the homework, the exercises they verified and when
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Claim {
+    pub homework: u32,
+    pub exercises: Vec<CertifiedExercise>,
+    // Seconds since the Unix epoch
+    pub timestamp: u64,
+    // The base58 address of the keypair, as Solana shows it
+    pub public_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Certificate {
+    #[serde(flatten)]
+    pub claim: Claim,
+    // The base58 ed25519 signature of the claim
+    pub signature: String,
+}
+
+impl Claim {
+    // The bytes the signature is made over
+    fn message(&self) -> Vec<u8> {
+        let mut message = SIGNING_CONTEXT.to_vec();
+        message.extend(serde_json::to_vec(self).unwrap());
+        message
+    }
+
+    pub fn sign(self, key: &SigningKey) -> Certificate {
+        let signature = key.sign(&self.message());
+        Certificate {
+            claim: self,
+            signature: bs58::encode(signature.to_bytes()).into_string(),
+        }
+    }
+}
+
+impl Certificate {
+    // Check that the signature is the one of the public key over the claim
+    pub fn check_signature(&self) -> Result<(), String> {
+        let public_key: [u8; 32] = bs58::decode(&self.claim.public_key)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or("the public key is not a base58 ed25519 public key")?;
+        let signature: [u8; 64] = bs58::decode(&self.signature)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or("the signature is not a base58 ed25519 signature")?;
+        VerifyingKey::from_bytes(&public_key)
+            .map_err(|_| "the public key is not a valid ed25519 public key")?
+            .verify_strict(&self.claim.message(), &Signature::from_bytes(&signature))
+            .map_err(|_| String::from("the signature doesn't match the certificate"))
+    }
+}
+
+// Read a keypair in the JSON format of the Solana CLI: an array of the
+// 32 bytes of the secret key followed by the 32 bytes of the public key
+pub fn load_keypair(path: &Path) -> Result<SigningKey, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let bytes: Vec<u8> = serde_json::from_str(&json).map_err(|_| "it is not a JSON array of bytes")?;
+    let bytes: [u8; 64] = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("it has {} bytes instead of 64", bytes.len()))?;
+    SigningKey::from_keypair_bytes(&bytes).map_err(|_| String::from("its public key doesn't match its secret key"))
+}
+
+// Verify every exercise of the homework and, if they all pass, write a
+// certificate of it signed with the keypair of the student.
+// As when grading, cached results are never trusted.
+pub fn certify(exercises: &[&Exercise], homework: u32, keypair_path: &Path, output: &Path) -> Result<(), ()> {
+    let key = match load_keypair(keypair_path) {
+        Ok(key) => key,
+        Err(e) => {
+            println!("Failed to read the keypair in {}: {}", keypair_path.display(), e);
+            return Err(());
+        }
+    };
+
+    let mut failed = Vec::new();
+    for exercise in exercises {
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(format!("Verifying {}...", exercise).as_str());
+        progress_bar.enable_steady_tick(100);
+        let outcome = evaluate(exercise, RunMode::Interactive, None).outcome;
+        progress_bar.finish_and_clear();
+        if !outcome.passed() {
+            failed.push(format!("{} ({})", exercise, outcome));
+        }
+    }
+    if !failed.is_empty() {
+        warn!("Homework {} isn't done yet, so it can't be certified.", homework);
+        println!("These exercises don't pass yet: {}", failed.join(", "));
+        return Err(());
+    }
+
+    let claim = Claim {
+        homework,
+        exercises: exercises
+            .iter()
+            .map(|exercise| CertifiedExercise {
+                name: exercise.name.clone(),
+                sha256: source_hash(&exercise.path).unwrap_or_default(),
+            })
+            .collect(),
+        timestamp: now(),
+        public_key: bs58::encode(key.verifying_key().to_bytes()).into_string(),
+    };
+    let certificate = claim.sign(&key);
+    if let Err(e) = fs::write(output, serde_json::to_string_pretty(&certificate).unwrap()) {
+        println!("Failed to write the certificate to {}: {}", output.display(), e);
+        return Err(());
+    }
+    success!("Certified homework {}!", homework);
+    println!(
+        "Wrote the certificate signed by {} to {}",
+        certificate.claim.public_key,
+        output.display()
+    );
+    Ok(())
+}
+
+// Check the signature of a certificate and that it covers every exercise
+// the course has for its homework. Needs nothing but the course itself.
+pub fn verify_certificate(exercises: &[Exercise], path: &Path, public_key: Option<&str>) -> Result<(), ()> {
+    let certificate: Certificate = match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(certificate) => certificate,
+        Err(e) => {
+            println!("Failed to read the certificate in {}: {}", path.display(), e);
+            return Err(());
+        }
+    };
+    let claim = &certificate.claim;
+
+    let mut problems = Vec::new();
+    if let Err(e) = certificate.check_signature() {
+        problems.push(e);
+    }
+    if let Some(expected) = public_key.filter(|expected| *expected != claim.public_key) {
+        problems.push(format!("it is signed by {} instead of {}", claim.public_key, expected));
+    }
+    let homework_exercises = exercises.iter().filter(|e| e.homework == Some(claim.homework));
+    for exercise in homework_exercises {
+        if !claim.exercises.iter().any(|certified| certified.name == exercise.name) {
+            problems.push(format!("it doesn't cover {}", exercise));
+        }
+    }
+
+    if !problems.is_empty() {
+        warn!("The certificate in {} is not valid:", path.display());
+        for problem in &problems {
+            println!("  - {}", problem);
+        }
+        return Err(());
+    }
+    success!("The certificate in {} is valid.", path.display());
+    println!(
+        "Homework {} was completed ({} exercise(s)) by {} at {} (Unix time)",
+        claim.homework,
+        claim.exercises.len(),
+        claim.public_key,
+        claim.timestamp
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_signature_covers_the_claim() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let claim = Claim {
+            homework: 3,
+            exercises: vec![CertifiedExercise {
+                name: "structs1".into(),
+                sha256: "00".into(),
+            }],
+            timestamp: 1_700_000_000,
+            public_key: bs58::encode(key.verifying_key().to_bytes()).into_string(),
+        };
+        let mut certificate = claim.sign(&key);
+        assert_eq!(certificate.check_signature(), Ok(()));
+
+        certificate.claim.exercises[0].sha256 = "01".into();
+        assert!(certificate.check_signature().is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use crate::exercise::Exercise;
+use crate::grade::{csv_field, GradeReport};
+use crate::junit::escape;
+use flate2::read::GzDecoder;
+use indicatif::ProgressBar;
+use std::cmp::Reverse;
+use std::env;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+// Directories of a checkout that are never copied for grading
+const SKIPPED_DIRS: &[&str] = &[".git", "target", ".rustlings-cache", ".rustlings-target"];
+// Where a checkout keeps the hints and solutions the student was shown
+const PROGRESS_FILE_NAME: &str = ".rustlings-progress.toml";
+
+// The grades of one student, or why their submission couldn't be graded
+pub struct Submission {
+    pub student: String,
+    pub grades: Result<GradeReport, String>,
+    // Whether the submission has a progress record. The hint and solution
+    // penalties come from it, so without one none are taken off.
+    pub has_progress: bool,
+}
+
+// Grade every student checkout or tarball in the submissions directory,
+// and write the class-wide results as CSV and as an HTML summary.
+// Each submission is graded by `rustlings grade` in a copy of its own in a
+// temporary directory, with the info.toml, hidden tests and expected outputs
+// of the course put in place of the student's. The hint and solution
+// penalties come from the student's own progress record, which they could
+// have edited or deleted, so the results flag submissions without one.
+pub fn grade_class(
+    exercises: &[&Exercise],
+    submissions_dir: &Path,
+    homework: Option<u32>,
+    csv_path: &Path,
+    html_path: &Path,
+) -> Result<(), ()> {
+    let submissions = match find_submissions(submissions_dir) {
+        Ok(submissions) if !submissions.is_empty() => submissions,
+        Ok(_) => {
+            println!("There are no submissions in {}.", submissions_dir.display());
+            return Err(());
+        }
+        Err(e) => {
+            println!("Failed to read {}: {}", submissions_dir.display(), e);
+            return Err(());
+        }
+    };
+
+    let mut graded = Vec::new();
+    for (student, path) in submissions {
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(format!("Grading {}...", student).as_str());
+        progress_bar.enable_steady_tick(100);
+        let submission = grade_submission(exercises, student, &path, homework);
+        progress_bar.finish_and_clear();
+
+        match &submission.grades {
+            Ok(report) if !submission.has_progress => println!(
+                "{:<24}\t{}/{}\t(no progress record, so no penalties)",
+                submission.student, report.score, report.max_score
+            ),
+            Ok(report) => println!("{:<24}\t{}/{}", submission.student, report.score, report.max_score),
+            Err(e) => warn!("Failed to grade {}", format!("{}: {}", submission.student, e)),
+        }
+        graded.push(submission);
+    }
+
+    let written = fs::write(csv_path, class_csv(exercises, &graded))
+        .and_then(|_| fs::write(html_path, class_html(exercises, &graded)));
+    if let Err(e) = written {
+        println!("Failed to write the class results: {}", e);
+        return Err(());
+    }
+    println!(
+        "Wrote the grades of {} students to {} and {}",
+        graded.len(),
+        csv_path.display(),
+        html_path.display()
+    );
+    Ok(())
+}
+
+// The student checkouts and tarballs in the directory, named after the
+// directory or the tarball without its extension
+fn find_submissions(dir: &Path) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut submissions = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let student = if path.is_dir() {
+            Some(name.as_str())
+        } else {
+            [".tar.gz", ".tgz", ".tar"]
+                .iter()
+                .find_map(|extension| name.strip_suffix(extension))
+        };
+        if let Some(student) = student {
+            submissions.push((student.to_string(), path));
+        }
+    }
+    submissions.sort();
+    Ok(submissions)
+}
+
+fn grade_submission(exercises: &[&Exercise], student: String, path: &Path, homework: Option<u32>) -> Submission {
+    let work_dir = env::temp_dir().join(format!("rustlings_class_{}_{}", process::id(), student));
+    let _ignored = fs::remove_dir_all(&work_dir);
+    let mut has_progress = false;
+    let grades = unpack(path, &work_dir)
+        .map_err(|e| format!("failed to unpack the submission: {}", e))
+        .and_then(|_| {
+            let root = checkout_root(&work_dir);
+            has_progress = root.join(PROGRESS_FILE_NAME).is_file();
+            install_course_files(exercises, &root).map_err(|e| format!("failed to set up the course: {}", e))?;
+            run_grade(&root, &work_dir.join("grades.json"), homework)
+        });
+    let _ignored = fs::remove_dir_all(&work_dir);
+    Submission {
+        student,
+        grades,
+        has_progress,
+    }
+}
+
+// Copy a checkout, or extract a tarball, into the directory
+fn unpack(path: &Path, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    if path.is_dir() {
+        return copy_dir(path, dir);
+    }
+    let file = File::open(path)?;
+    if path.extension().is_some_and(|ext| ext == "tar") {
+        tar::Archive::new(file).unpack(dir)
+    } else {
+        tar::Archive::new(GzDecoder::new(file)).unpack(dir)
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)?.flatten() {
+        let name = entry.file_name();
+        if SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(&name))?;
+        } else {
+            fs::copy(&path, to.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+// Tarballs usually hold the checkout in a single top-level directory
+fn checkout_root(dir: &Path) -> PathBuf {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [only] if only.is_dir() && !dir.join("info.toml").exists() => only.clone(),
+        _ => dir.to_path_buf(),
+    }
+}
+
+// Put the files the grading depends on in place, as the course has them,
+// so that students can't change how they are graded
+fn install_course_files(exercises: &[&Exercise], root: &Path) -> io::Result<()> {
+    fs::copy("info.toml", root.join("info.toml"))?;
+    let vendor_dir = Path::new("vendor");
+    if vendor_dir.is_dir() {
+        copy_dir(vendor_dir, &root.join("vendor"))?;
+    }
+    for exercise in exercises {
+        let mut orig_path = exercise.path.clone().into_os_string();
+        orig_path.push(".orig");
+        let course_files = [
+            exercise.hidden_tests.clone(),
+            Some(PathBuf::from(orig_path)),
+            Some(exercise.path.with_extension("stdin")),
+            Some(exercise.path.with_extension("stdout")),
+        ];
+        for file in course_files.iter().flatten().filter(|file| file.is_file()) {
+            let target = root.join(file);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(file, target)?;
+        }
+    }
+    Ok(())
+}
+
+// Run `rustlings grade` in the checkout, and read the grades it wrote
+fn run_grade(root: &Path, grades_path: &Path, homework: Option<u32>) -> Result<GradeReport, String> {
+    let exe = env::current_exe().map_err(|e| e.to_string())?;
+    let mut command = Command::new(exe);
+    command.arg("grade");
+    if let Some(homework) = homework {
+        command.arg(homework.to_string());
+    }
+    let status = command
+        .arg("--output")
+        .arg(grades_path)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(String::from("grading failed"));
+    }
+    let json = fs::read_to_string(grades_path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+// One line per student with the score of every exercise and the total.
+// Submissions that couldn't be graded only have the reason why.
+fn class_csv(exercises: &[&Exercise], submissions: &[Submission]) -> String {
+    let mut csv = String::from("student");
+    for exercise in exercises {
+        write!(csv, ",{}", csv_field(&exercise.name)).unwrap();
+    }
+    csv.push_str(",score,max_score,progress,error\n");
+
+    for submission in submissions {
+        csv.push_str(&csv_field(&submission.student));
+        match &submission.grades {
+            Ok(report) => {
+                for exercise in exercises {
+                    let score = report.exercises.iter().find(|grade| grade.name == exercise.name);
+                    match score {
+                        Some(grade) => write!(csv, ",{}", grade.score).unwrap(),
+                        None => csv.push(','),
+                    }
+                }
+                let progress = if submission.has_progress { "present" } else { "missing" };
+                writeln!(csv, ",{},{},{},", report.score, report.max_score, progress).unwrap();
+            }
+            Err(e) => {
+                csv.push_str(&",".repeat(exercises.len() + 3));
+                writeln!(csv, ",{}", csv_field(e)).unwrap();
+            }
+        }
+    }
+    csv
+}
+
+// How many of the graded students failed each exercise, most failed first
+fn failures_by_exercise<'a>(exercises: &[&'a Exercise], submissions: &[Submission]) -> Vec<(&'a Exercise, usize)> {
+    let mut failures: Vec<(&Exercise, usize)> = exercises
+        .iter()
+        .map(|exercise| {
+            let failed = submissions
+                .iter()
+                .filter_map(|submission| submission.grades.as_ref().ok())
+                .filter_map(|report| report.exercises.iter().find(|grade| grade.name == exercise.name))
+                .filter(|grade| !grade.outcome.passed())
+                .count();
+            (*exercise, failed)
+        })
+        .collect();
+    // A stable sort keeps the order of the course between equally failed exercises
+    failures.sort_by_key(|(_, failed)| Reverse(*failed));
+    failures
+}
+
+// A page showing which exercises most students failed, and the score of every student
+fn class_html(exercises: &[&Exercise], submissions: &[Submission]) -> String {
+    let graded = submissions.iter().filter(|submission| submission.grades.is_ok()).count();
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Rustlings class summary</title>\n\
+         <style>\nbody { font-family: sans-serif; }\ntable { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }\n\
+         .bar { background: #d9534f; height: 1em; }\n</style>\n</head>\n<body>\n\
+         <h1>Rustlings class summary</h1>\n",
+    );
+    writeln!(
+        html,
+        "<p>{} of {} submissions graded.</p>",
+        graded,
+        submissions.len()
+    )
+    .unwrap();
+
+    html.push_str("<h2>Exercises by failures</h2>\n<table>\n<tr><th>Exercise</th><th>Failed</th><th></th></tr>\n");
+    for (exercise, failed) in failures_by_exercise(exercises, submissions) {
+        let percent = (failed * 100).checked_div(graded).unwrap_or(0);
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}/{} ({}%)</td><td><div class=\"bar\" style=\"width: {}px\"></div></td></tr>",
+            escape(&exercise.name),
+            failed,
+            graded,
+            percent,
+            percent * 2
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+
+    html.push_str(
+        "<h2>Students</h2>\n<p>The hint and solution penalties come from the progress record of each \
+         submission, which the student could have edited or deleted.</p>\n\
+         <table>\n<tr><th>Student</th><th>Score</th></tr>\n",
+    );
+    for submission in submissions {
+        let score = match &submission.grades {
+            Ok(report) if !submission.has_progress => {
+                format!("{}/{} (no progress record, so no penalties)", report.score, report.max_score)
+            }
+            Ok(report) => format!("{}/{}", report.score, report.max_score),
+            Err(e) => format!("not graded: {}", e),
+        };
+        writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            escape(&submission.student),
+            escape(&score)
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_submissions() {
+        let submissions = find_submissions(Path::new("tests/fixture/class/submissions")).unwrap();
+        let students: Vec<&str> = submissions.iter().map(|(student, _)| student.as_str()).collect();
+        assert_eq!(students, ["alice", "bob", "carol", "dave"]);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::exercise::{crate_files, normalize_path, Exercise, Mode, State};
+use crate::protected::{fingerprint, protected_region};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Check that info.toml and the exercise files under `homeworks_dir` agree,
+// and print every problem found. Meant for the maintainers of the course.
+pub fn check_course(exercises: &[Exercise], homeworks_dir: &Path) -> Result<(), ()> {
+    let problems = find_problems(exercises, homeworks_dir);
+    if problems.is_empty() {
+        success!("The course is consistent: {} exercise(s) checked.", exercises.len());
+        return Ok(());
+    }
+
+    warn!("Found {} problem(s) in the course:", problems.len());
+    for problem in &problems {
+        println!("  - {}", problem);
+    }
+    Err(())
+}
+
+// Every problem with the course, in the order of the checks
+pub fn find_problems(exercises: &[Exercise], homeworks_dir: &Path) -> Vec<String> {
+    let files: Vec<PathBuf> = crate_files(homeworks_dir)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+
+    let mut problems = Vec::new();
+    problems.extend(duplicate_names(exercises));
+    problems.extend(missing_paths(exercises));
+    problems.extend(unlisted_files(exercises, &files));
+    problems.extend(missing_markers(exercises));
+    problems.extend(mismatched_mod_files(exercises, &files));
+    problems.extend(missing_hints(exercises));
+    problems.extend(stale_fingerprints(exercises));
+    problems.extend(doctests(exercises));
+    problems.extend(invalid_regexes(exercises));
+    problems
+}
+
+fn duplicate_names(exercises: &[Exercise]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    exercises
+        .iter()
+        .filter(|e| !seen.insert(&e.name) && reported.insert(&e.name))
+        .map(|e| format!("the name `{}` is used by more than one exercise", e.name))
+        .collect()
+}
+
+fn missing_paths(exercises: &[Exercise]) -> Vec<String> {
+    let mut problems: Vec<String> = exercises
+        .iter()
+        .filter(|e| !e.path.exists())
+        .map(|e| format!("{} points to {}, which doesn't exist", e.name, e.path.display()))
+        .collect();
+    for exercise in exercises {
+        if let Some(hidden_tests) = exercise.hidden_tests.as_ref().filter(|path| !path.exists()) {
+            problems.push(format!(
+                "the hidden tests of {} point to {}, which doesn't exist",
+                exercise.name,
+                hidden_tests.display()
+            ));
+        }
+    }
+    problems
+}
+
+// Rust files under the homeworks directory that no exercise uses
+fn unlisted_files(exercises: &[Exercise], files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|file| file.file_name().is_some_and(|name| name != "mod.rs"))
+        .filter(|file| !exercises.iter().any(|e| belongs_to(file, e)))
+        .map(|file| format!("{} is not listed in info.toml", file.display()))
+        .collect()
+}
+
+fn missing_markers(exercises: &[Exercise]) -> Vec<String> {
+    exercises
+        .iter()
+        .filter(|e| e.path.exists() && e.state() == State::Done)
+        .map(|e| format!("{} has no `I AM NOT DONE` marker", e.path.display()))
+        .collect()
+}
+
+// The modules every mod.rs declares must be the files and directories next
+// to it. The crates of cargo exercises are left alone.
+fn mismatched_mod_files(exercises: &[Exercise], files: &[PathBuf]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mod_files = files
+        .iter()
+        .filter(|file| file.file_name().is_some_and(|name| name == "mod.rs"))
+        .filter(|file| !exercises.iter().any(|e| matches!(e.mode, Mode::Cargo) && belongs_to(file, e)));
+    for mod_file in mod_files {
+        let dir = mod_file.parent().unwrap_or(Path::new("."));
+        let source = fs::read_to_string(mod_file).unwrap_or_default();
+        let declared = declared_modules(&source);
+        let present = present_modules(dir);
+
+        for module in declared.difference(&present) {
+            problems.push(format!(
+                "{} declares `{}`, but there is no {}.rs or {}/ next to it",
+                mod_file.display(),
+                module,
+                module,
+                module
+            ));
+        }
+        for module in present.difference(&declared) {
+            problems.push(format!("{} doesn't declare `{}`", mod_file.display(), module));
+        }
+    }
+    problems
+}
+
+fn missing_hints(exercises: &[Exercise]) -> Vec<String> {
+    exercises
+        .iter()
+        .filter(|e| e.hint_levels().iter().all(|level| level.trim().is_empty()))
+        .map(|e| format!("{} has no hint", e.name))
+        .collect()
+}
+
+// The fingerprints in info.toml must match the protected regions of the
+// exercises, which are still the original ones in the course itself
+fn stale_fingerprints(exercises: &[Exercise]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for exercise in exercises {
+        let expected = match &exercise.protected_fingerprint {
+            Some(expected) => expected,
+            None => continue,
+        };
+        let source = fs::read_to_string(&exercise.path).unwrap_or_default();
+        let actual = fingerprint(&protected_region(exercise, &source).unwrap_or_default());
+        if *expected != actual {
+            problems.push(format!(
+                "the protected_fingerprint of {} doesn't match its protected region, whose fingerprint is {}",
+                exercise.name, actual
+            ));
+        }
+    }
+    problems
+}
+
+// Cargo exercises are checked by running their test binaries, which don't
+// include doctests, so tests written as doc examples would never run
+fn doctests(exercises: &[Exercise]) -> Vec<String> {
+    exercises
+        .iter()
+        .filter(|e| matches!(e.mode, Mode::Cargo))
+        .flat_map(|e| e.source_files())
+        .filter(|file| has_doctests(&fs::read_to_string(file).unwrap_or_default()))
+        .map(|file| format!("{} has doctests, which are never run", file.display()))
+        .collect()
+}
+
+// Whether the doc comments of the source have a code block rustdoc would test
+fn has_doctests(source: &str) -> bool {
+    let mut in_block = false;
+    for line in source.lines() {
+        let line = line.trim_start();
+        let doc = match line.strip_prefix("///").or_else(|| line.strip_prefix("//!")) {
+            Some(doc) => doc.trim(),
+            None => continue,
+        };
+        let info = match doc.strip_prefix("```") {
+            Some(info) => info.trim(),
+            None => continue,
+        };
+        if !in_block && !matches!(info, "text" | "ignore") {
+            return true;
+        }
+        in_block = !in_block;
+    }
+    false
+}
+
+fn invalid_regexes(exercises: &[Exercise]) -> Vec<String> {
+    exercises
+        .iter()
+        .filter_map(|e| {
+            let error = e.regex_error()?;
+            Some(format!("the expected_stdout_regex of {} is not a valid regex:\n{}", e.name, error))
+        })
+        .collect()
+}
+
+// Whether the file is the exercise or its hidden tests,
+// or part of the crate of a cargo exercise
+fn belongs_to(file: &Path, exercise: &Exercise) -> bool {
+    let file = normalize_path(file);
+    let path = normalize_path(&exercise.path);
+    if exercise.hidden_tests.as_deref().map(normalize_path) == Some(file.clone()) {
+        return true;
+    }
+    match exercise.mode {
+        Mode::Cargo => file.starts_with(path),
+        _ => file == path,
+    }
+}
+
+// The names of the `mod name;` declarations in the source
+fn declared_modules(source: &str) -> BTreeSet<String> {
+    source
+        .lines()
+        .map(|line| line.trim().trim_start_matches("pub ").trim())
+        .filter_map(|line| line.strip_prefix("mod ")?.strip_suffix(';'))
+        .map(|name| name.trim().trim_start_matches("r#").to_string())
+        .collect()
+}
+
+// The modules a mod.rs could declare: the other Rust files next to it and
+// the directories beside it, except for the crates of cargo exercises
+fn present_modules(dir: &Path) -> BTreeSet<String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return BTreeSet::new(),
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')))
+        .filter_map(|path| {
+            if path.is_dir() {
+                if path.join("Cargo.toml").exists() {
+                    return None;
+                }
+                path.file_name().map(|name| name.to_string_lossy().to_string())
+            } else if path.extension().is_some_and(|ext| ext == "rs") && !path.ends_with("mod.rs") {
+                path.file_stem().map(|stem| stem.to_string_lossy().to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_declared_modules() {
+        let source = "mod functions;\npub mod r#if;\n\n// mod commented;\nmod inline {}\n";
+        let expected: BTreeSet<String> = ["functions", "if"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(declared_modules(source), expected);
+    }
+
+    #[test]
+    fn test_has_doctests() {
+        assert!(has_doctests("/// ```\n/// assert!(true);\n/// ```\npub fn f() {}\n"));
+        assert!(has_doctests("//! ```rust\n//! assert!(true);\n//! ```\n"));
+        assert!(!has_doctests("/// ```text\n/// output\n/// ```\npub fn f() {}\n"));
+        assert!(!has_doctests("// ```\n// not a doc comment\n// ```\n"));
+    }
+}
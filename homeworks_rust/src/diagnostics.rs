@@ -0,0 +1,235 @@
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::Path;
+
+// A diagnostic rustc printed with `--error-format=json`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    // "error", "warning", "note", "help" or "failure-note"
+    pub level: String,
+    #[serde(default)]
+    pub spans: Vec<DiagnosticSpan>,
+    #[serde(default)]
+    pub children: Vec<Diagnostic>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct DiagnosticCode {
+    // The error code, e.g. E0384
+    pub code: String,
+}
+
+// A piece of the source a diagnostic points at
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    // Whether this is where the problem is, rather than some context for it
+    pub is_primary: bool,
+    pub label: Option<String>,
+    // The source lines the span covers
+    #[serde(default)]
+    pub text: Vec<SpanText>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SpanText {
+    pub text: String,
+}
+
+// A compiler error with a code, as remembered after the compilation
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CompileError {
+    // The error code, e.g. E0382
+    pub code: String,
+    // Where the error points in the source, e.g. `move_semantics1.rs:13:5`
+    pub location: Option<String>,
+    // The first source line the error points at
+    pub source_line: Option<String>,
+}
+
+impl Diagnostic {
+    // Where the problem is in the source, if the diagnostic points at it
+    pub fn primary_span(&self) -> Option<&DiagnosticSpan> {
+        self.spans.iter().find(|span| span.is_primary)
+    }
+
+    // The summaries rustc adds after the actual diagnostics,
+    // like "aborting due to 2 previous errors"
+    fn is_summary(&self) -> bool {
+        self.spans.is_empty() && (self.level == "failure-note" || self.message.starts_with("aborting due to"))
+    }
+}
+
+// A line of cargo's JSON output; only compiler messages carry a diagnostic
+#[derive(Deserialize)]
+struct CargoMessage {
+    message: Option<Diagnostic>,
+}
+
+// The parsed output of a compilation
+pub struct Diagnostics {
+    pub diagnostics: Vec<Diagnostic>,
+    // Lines that weren't JSON diagnostics, e.g. from the linker
+    pub other_output: Vec<String>,
+}
+
+impl Diagnostics {
+    // Parse the stderr of rustc, which prints one JSON diagnostic per line
+    pub fn parse(stderr: &str) -> Diagnostics {
+        let mut diagnostics = Vec::new();
+        let mut other_output = Vec::new();
+        for line in stderr.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str::<Diagnostic>(line) {
+                Ok(diagnostic) if !diagnostic.is_summary() => diagnostics.push(diagnostic),
+                Ok(_) => {}
+                Err(_) => other_output.push(line.to_string()),
+            }
+        }
+        Diagnostics {
+            diagnostics,
+            other_output,
+        }
+    }
+
+    // Parse the output of cargo with `--message-format=json`, which wraps the
+    // diagnostics of rustc in one JSON message per line on stdout.
+    // Spans are made relative to the current directory instead of the crate.
+    pub fn parse_cargo(stdout: &str, stderr: &str, crate_dir: &Path) -> Diagnostics {
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        for line in stdout.lines() {
+            let message = match serde_json::from_str::<CargoMessage>(line) {
+                Ok(CargoMessage { message: Some(message), .. }) if !message.is_summary() => message,
+                _ => continue,
+            };
+            // Targets that share a source file report the same diagnostics
+            if diagnostics.contains(&message) {
+                continue;
+            }
+            diagnostics.push(message);
+        }
+        for span in diagnostics.iter_mut().flat_map(|d| d.spans.iter_mut()) {
+            span.file_name = crate_dir.join(&span.file_name).to_string_lossy().to_string();
+        }
+
+        // Cargo reports its own errors, like unresolvable dependencies, on stderr
+        let other_output = stderr
+            .lines()
+            .filter(|line| line.starts_with("error") && !line.starts_with("error: could not compile"))
+            .map(String::from)
+            .collect();
+        Diagnostics {
+            diagnostics,
+            other_output,
+        }
+    }
+
+    // Keep the source of a file from the diagnostics that point into it,
+    // showing them under the given name instead
+    pub fn hide_source(&mut self, file_name: &str, shown_name: &str) {
+        fn hide(diagnostic: &mut Diagnostic, file_name: &str, shown_name: &str) {
+            for span in diagnostic.spans.iter_mut().filter(|span| span.file_name.ends_with(file_name)) {
+                span.file_name = shown_name.to_string();
+                span.text.clear();
+            }
+            for child in &mut diagnostic.children {
+                hide(child, file_name, shown_name);
+            }
+        }
+        for diagnostic in &mut self.diagnostics {
+            hide(diagnostic, file_name, shown_name);
+        }
+    }
+
+    // The first error of every code, in the order rustc reported them
+    pub fn errors(&self) -> Vec<CompileError> {
+        let mut errors: Vec<CompileError> = Vec::new();
+        for diagnostic in self.diagnostics.iter().filter(|d| d.level == "error") {
+            let code = match &diagnostic.code {
+                Some(code) if !errors.iter().any(|e| e.code == code.code) => code.code.clone(),
+                _ => continue,
+            };
+            let span = diagnostic.primary_span();
+            errors.push(CompileError {
+                code,
+                location: span.map(|span| format!("{}:{}:{}", span.file_name, span.line_start, span.column_start)),
+                source_line: span.and_then(|span| span.text.first()).map(|text| text.text.clone()),
+            });
+        }
+        errors
+    }
+
+    // A compact summary: the level, code and message of every diagnostic,
+    // where it points to, and the help rustc offers for it
+    pub fn render(&self) -> String {
+        let mut rendered = String::new();
+        for diagnostic in &self.diagnostics {
+            let heading = match &diagnostic.code {
+                Some(code) => format!("{}[{}]", diagnostic.level, code.code),
+                None => diagnostic.level.clone(),
+            };
+            let heading = match diagnostic.level.as_str() {
+                "error" => style(heading).red().bold(),
+                "warning" => style(heading).yellow().bold(),
+                _ => style(heading).bold(),
+            };
+            writeln!(rendered, "{}: {}", heading, style(&diagnostic.message).bold()).unwrap();
+
+            if let Some(span) = diagnostic.primary_span() {
+                let location = format!("{}:{}:{}", span.file_name, span.line_start, span.column_start);
+                write!(rendered, "  {} {}", style("-->").blue().bold(), location).unwrap();
+                match &span.label {
+                    Some(label) => writeln!(rendered, ": {}", label).unwrap(),
+                    None => rendered.push('\n'),
+                }
+            }
+            for child in diagnostic.children.iter().filter(|child| !child.message.is_empty()) {
+                writeln!(rendered, "  {} {}: {}", style("=").blue().bold(), child.level, child.message).unwrap();
+            }
+        }
+        for line in &self.other_output {
+            writeln!(rendered, "{}", line).unwrap();
+        }
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const STDERR: &str = r#"{"$message_type":"diagnostic","message":"cannot assign twice to immutable variable `x`","code":{"code":"E0384","explanation":"..."},"level":"error","spans":[{"file_name":"variables5.rs","byte_start":55,"byte_end":60,"line_start":8,"line_end":8,"column_start":5,"column_end":10,"is_primary":true,"text":[{"text":"    x = 3;","highlight_start":5,"highlight_end":10}],"label":"cannot assign twice to immutable variable","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"consider making this binding mutable","code":null,"level":"help","spans":[],"children":[],"rendered":null}],"rendered":"error[E0384]: ..."}
+{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}
+{"$message_type":"diagnostic","message":"For more information about this error, try `rustc --explain E0384`.","code":null,"level":"failure-note","spans":[],"children":[],"rendered":"..."}
+"#;
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let diagnostics = Diagnostics::parse(STDERR);
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert!(diagnostics.other_output.is_empty());
+        assert_eq!(
+            diagnostics.errors(),
+            vec![CompileError {
+                code: String::from("E0384"),
+                location: Some(String::from("variables5.rs:8:5")),
+                source_line: Some(String::from("    x = 3;")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_render_diagnostics() {
+        let rendered = console::strip_ansi_codes(&Diagnostics::parse(STDERR).render()).to_string();
+        assert_eq!(
+            rendered,
+            "error[E0384]: cannot assign twice to immutable variable `x`\n  \
+             --> variables5.rs:8:5: cannot assign twice to immutable variable\n  \
+             = help: consider making this binding mutable\n"
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use console::style;
+use similar::{ChangeTag, TextDiff};
+
+// How many unchanged lines are shown around each change
+const CONTEXT_LINES: usize = 3;
+
+// Print a colored line diff of two texts, with removed lines in red
+// and added lines in green
+pub fn print_diff(old: &str, new: &str) {
+    let diff = TextDiff::from_lines(old, new);
+    for (index, group) in diff.grouped_ops(CONTEXT_LINES).iter().enumerate() {
+        if index > 0 {
+            println!("{}", style("...").dim());
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let line = change.value().trim_end_matches('\n');
+                match change.tag() {
+                    ChangeTag::Delete => println!("{}", style(format!("-{}", line)).red()),
+                    ChangeTag::Insert => println!("{}", style(format!("+{}", line)).green()),
+                    ChangeTag::Equal => println!(" {}", line),
+                }
+            }
+        }
+    }
+}
@@ -1,16 +1,27 @@
+use crate::diagnostics::{CompileError, Diagnostics};
+use crate::grade::Grading;
+use crate::limits::{Limits, Stopped};
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
 use std::process::{self, Command};
 
 const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format=json"];
+// The edition exercises are built with by default
+const DEFAULT_EDITION: &str = "2021";
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
+// Where the crates of cargo exercises are built, outside of ./homeworks
+// so that the build output doesn't set off watch mode
+const CARGO_TARGET_DIR_PATH: &str = ".rustlings-target";
+// The vendored registry the dependencies of cargo exercises are resolved from
+const VENDOR_DIR_PATH: &str = "vendor";
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -23,8 +34,14 @@ fn temp_file() -> String {
     format!("./temp_{}_{}", process::id(), thread_id)
 }
 
+// Get a directory for the throwaway crate Clippy lints an exercise in,
+// which is unique to this thread like temp_file()
+fn clippy_crate_dir() -> PathBuf {
+    env::temp_dir().join(format!("rustlings_clippy_{}", &temp_file()[2..]))
+}
+
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -33,25 +50,220 @@ pub enum Mode {
     Test,
     // Indicates that the exercise should be linted with clippy
     Clippy,
+    // Indicates that the exercise should fail to compile with the expected errors
+    #[serde(rename = "compile-fail")]
+    CompileFail,
+    // Indicates that the exercise is a crate that should be built and tested with cargo
+    Cargo,
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mode = match self {
+            Mode::Compile => "compile",
+            Mode::Test => "test",
+            Mode::Clippy => "clippy",
+            Mode::CompileFail => "compile-fail",
+            Mode::Cargo => "cargo",
+        };
+        write!(f, "{}", mode)
+    }
 }
 
 #[derive(Deserialize)]
 pub struct ExerciseList {
+    // The settings of every exercise that doesn't set its own
+    #[serde(default)]
+    pub defaults: Defaults,
     pub exercises: Vec<Exercise>,
 }
 
+// The course-wide settings from the [defaults] section of info.toml.
+// Every exercise can override them one by one in its own entry; whatever
+// neither of them sets falls back to the built-in default of the setting.
+#[derive(Deserialize, Default)]
+pub struct Defaults {
+    #[serde(flatten)]
+    pub limits: Limits,
+    #[serde(flatten)]
+    pub build: BuildSettings,
+    #[serde(flatten)]
+    pub grading: Grading,
+    // How many failed verifications unlock the reference solution of an exercise
+    pub solution_unlock_attempts: Option<u32>,
+}
+
+// Settings that are given in [defaults] and overridden per exercise
+pub trait Overridable {
+    // Fill in the settings that aren't set with the given defaults
+    fn or(&self, defaults: &Self) -> Self;
+}
+
+macro_rules! overridable {
+    ($settings:ident { $($field:ident),* }) => {
+        impl Overridable for $settings {
+            fn or(&self, defaults: &Self) -> Self {
+                $settings {
+                    $($field: self.$field.clone().or_else(|| defaults.$field.clone()),)*
+                }
+            }
+        }
+    };
+}
+
+overridable!(Limits { timeout_secs, memory_limit_mb, output_limit_kb });
+overridable!(BuildSettings { edition, rustc_flags, deny_warnings });
+overridable!(Grading { weight, hint_penalty, solution_penalty });
+
+// How the compiler is invoked on an exercise
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct BuildSettings {
+    // The Rust edition, e.g. "2021". Cargo exercises take it from their manifest.
+    pub edition: Option<String>,
+    // Extra flags passed to rustc, replacing those of the defaults
+    pub rustc_flags: Option<Vec<String>>,
+    // Whether compiler warnings fail the build
+    pub deny_warnings: Option<bool>,
+}
+
+impl BuildSettings {
+    pub fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or(DEFAULT_EDITION)
+    }
+
+    // The flags passed to rustc besides the edition
+    pub fn flags(&self) -> Vec<String> {
+        let mut flags = self.rustc_flags.clone().unwrap_or_default();
+        if self.deny_warnings == Some(true) {
+            flags.extend([String::from("-D"), String::from("warnings")]);
+        }
+        flags
+    }
+
+    // The arguments passed to rustc for these settings
+    pub fn rustc_args(&self) -> Vec<String> {
+        let mut args = vec![String::from("--edition"), self.edition().to_string()];
+        args.extend(self.flags());
+        args
+    }
+}
+
+impl ExerciseList {
+    // The exercises of the course, with the course-wide defaults applied
+    pub fn into_exercises(self) -> Vec<Exercise> {
+        let defaults = self.defaults;
+        self.exercises
+            .into_iter()
+            .map(|exercise| Exercise {
+                limits: exercise.limits.or(&defaults.limits),
+                build: exercise.build.or(&defaults.build),
+                grading: exercise.grading.or(&defaults.grading),
+                solution_unlock_attempts: exercise
+                    .solution_unlock_attempts
+                    .or(defaults.solution_unlock_attempts),
+                ..exercise
+            })
+            .collect()
+    }
+}
+
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
 #[derive(Deserialize, Debug, Clone)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
-    // The path to the file containing the exercise's source code
+    // The path to the file containing the exercise's source code,
+    // or to the crate directory of a cargo exercise
     pub path: PathBuf,
+    // The number of the homework the exercise belongs to, if any
+    #[serde(default)]
+    pub homework: Option<u32>,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
     // The hint text associated with the exercise
+    #[serde(default)]
     pub hint: String,
+    // Progressively stronger hints, revealed one level at a time.
+    // These take the place of `hint` when given.
+    #[serde(default)]
+    pub hints: Vec<String>,
+    // Hints for specific compiler errors, keyed by error code like E0384
+    #[serde(default)]
+    pub hints_by_error: BTreeMap<String, String>,
+    // The codes of the errors a compile-fail exercise must fail with
+    #[serde(default)]
+    pub expected_errors: Vec<String>,
+    // The input the binary is run with, instead of a `.stdin` file next to the exercise
+    #[serde(default)]
+    pub stdin: Option<String>,
+    // What the binary must print, instead of a `.stdout` file next to the exercise
+    #[serde(default)]
+    pub expected_stdout: Option<String>,
+    // A regex that what the binary prints must match
+    #[serde(default)]
+    pub expected_stdout_regex: Option<String>,
+    // The Clippy lints a clippy exercise must not trigger, besides the
+    // warn-by-default ones, e.g. "clippy::float_cmp"
+    #[serde(default)]
+    pub lints: Vec<String>,
+    // Tests of a test exercise that the student doesn't get to see or change.
+    // The file is added to the test harness as a module below the exercise.
+    #[serde(default)]
+    pub hidden_tests: Option<PathBuf>,
+    // The first and last line of the part of the exercise file the student
+    // must not change, instead of the protected regions marked in the file
+    #[serde(default)]
+    pub protected_lines: Option<[usize; 2]>,
+    // The fingerprint of the protected regions of the original exercise,
+    // for when the original isn't available to compare them with
+    #[serde(default)]
+    pub protected_fingerprint: Option<String>,
+    // The limits on the resources the compiled exercise may use
+    #[serde(default, flatten)]
+    pub limits: Limits,
+    // How the compiler is invoked on the exercise
+    #[serde(default, flatten)]
+    pub build: BuildSettings,
+    // How the exercise is scored by `rustlings grade`
+    #[serde(default, flatten)]
+    pub grading: Grading,
+    // The path to the reference solution of the exercise, if it has one
+    #[serde(default)]
+    pub solution: Option<PathBuf>,
+    // How many failed verifications unlock the reference solution
+    #[serde(default)]
+    pub solution_unlock_attempts: Option<u32>,
+}
+
+// An exercise with nothing but its name, path and mode set, for the tests
+// to build on with `Exercise { name, path, mode, ..Default::default() }`
+#[cfg(test)]
+impl Default for Exercise {
+    fn default() -> Self {
+        Exercise {
+            name: String::new(),
+            path: PathBuf::new(),
+            homework: None,
+            mode: Mode::Compile,
+            hint: String::new(),
+            hints: Vec::new(),
+            hints_by_error: BTreeMap::new(),
+            expected_errors: Vec::new(),
+            stdin: None,
+            expected_stdout: None,
+            expected_stdout_regex: None,
+            lints: Vec::new(),
+            hidden_tests: None,
+            protected_lines: None,
+            protected_fingerprint: None,
+            limits: Limits::default(),
+            build: BuildSettings::default(),
+            grading: Grading::default(),
+            solution: None,
+            solution_unlock_attempts: None,
+        }
+    }
 }
 
 // An enum to track of the state of an Exercise.
@@ -75,6 +287,32 @@ pub struct ContextLine {
     pub important: bool,
 }
 
+// What a compile exercise must print for its output to be accepted
+#[derive(PartialEq, Debug)]
+pub enum ExpectedOutput {
+    // Exactly this text, apart from trailing newlines
+    Exact(String),
+    // Any text that matches this regex as a whole, apart from trailing newlines
+    Regex(String),
+}
+
+impl ExpectedOutput {
+    pub fn matches(&self, stdout: &str) -> bool {
+        match self {
+            ExpectedOutput::Exact(expected) => {
+                normalize_output(expected) == normalize_output(stdout)
+            }
+            ExpectedOutput::Regex(regex) => Regex::new(&format!("^(?:{})$", regex))
+                .is_ok_and(|re| re.is_match(&normalize_output(stdout))),
+        }
+    }
+}
+
+// Ignore Windows line endings and trailing newlines when comparing output
+pub fn normalize_output(output: &str) -> String {
+    output.replace("\r\n", "\n").trim_end_matches('\n').to_string()
+}
+
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
@@ -86,6 +324,11 @@ impl<'a> CompiledExercise<'a> {
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
         self.exercise.run()
     }
+
+    // Copy the compiled binary to the given path
+    pub fn save_binary(&self, path: &Path) -> io::Result<()> {
+        fs::copy(temp_file(), path).map(|_| ())
+    }
 }
 
 // A representation of an already executed binary
@@ -95,6 +338,22 @@ pub struct ExerciseOutput {
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // Why the binary was stopped before it exited, if it was
+    pub stopped: Option<Stopped>,
+    // The errors with a code a failed compilation hit, like E0384
+    pub errors: Vec<CompileError>,
+}
+
+// A line of cargo's JSON output; built test binaries carry their executable
+#[derive(Deserialize)]
+struct CargoArtifact {
+    executable: Option<PathBuf>,
+    profile: Option<CargoProfile>,
+}
+
+#[derive(Deserialize)]
+struct CargoProfile {
+    test: bool,
 }
 
 struct FileHandle;
@@ -106,57 +365,41 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
         let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+            Mode::Compile | Mode::CompileFail => Command::new("rustc")
+                .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(RUSTC_JSON_ARGS)
+                .args(self.build.rustc_args())
                 .output(),
+            Mode::Test => self.test_harness_source().and_then(|source| {
+                let output = Command::new("rustc")
+                    .args(["--test", source.to_str().unwrap(), "-o", &temp_file()])
+                    .args(RUSTC_JSON_ARGS)
+                    .args(self.build.rustc_args())
+                    .output();
+                if source != self.path {
+                    let _ignored = remove_file(&source);
+                }
+                output
+            }),
             Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2018"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
                 Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args([self.path.to_str().unwrap(), "-o", &temp_file()])
                     .args(RUSTC_COLOR_ARGS)
+                    .args(self.build.rustc_args())
                     .output()
                     .expect("Failed to compile!");
-                // Due to an issue with Clippy, a cargo clean is required to catch all lints.
-                // See https://github.com/rust-lang/rust-clippy/issues/2604
-                // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
-                // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .output()
-                    .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings","-D","clippy::float_cmp"])
-                    .output()
+                self.clippy()
             }
+            Mode::Cargo => self
+                .cargo_command("build")
+                .args(["--all-targets", "--message-format=json"])
+                .output(),
         }
         .expect("Failed to run 'compile' command.");
 
@@ -167,84 +410,307 @@ path = "{}.rs""#,
             })
         } else {
             clean();
+            let stderr = String::from_utf8_lossy(&cmd.stderr).to_string();
+            // Clippy's output comes from cargo and is kept as is
+            let (stderr, errors) = match self.mode {
+                Mode::Clippy => (stderr, Vec::new()),
+                Mode::Cargo => {
+                    let stdout = String::from_utf8_lossy(&cmd.stdout);
+                    let diagnostics = Diagnostics::parse_cargo(&stdout, &stderr, &self.path);
+                    (diagnostics.render(), diagnostics.errors())
+                }
+                _ => {
+                    let mut diagnostics = Diagnostics::parse(&stderr);
+                    if let Some(hidden_tests) = &self.hidden_tests {
+                        diagnostics.hide_source(&hidden_tests.to_string_lossy(), "hidden tests");
+                    }
+                    (diagnostics.render(), diagnostics.errors())
+                }
+            };
             Err(ExerciseOutput {
                 stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                stderr,
+                stopped: None,
+                errors,
             })
         }
     }
 
+    // Reuse a binary compiled earlier instead of invoking the compiler
+    pub fn load_compiled(&self, binary: &Path) -> Option<CompiledExercise<'_>> {
+        fs::copy(binary, temp_file()).ok()?;
+        Some(CompiledExercise {
+            exercise: self,
+            _handle: FileHandle,
+        })
+    }
+
     fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
         let arg = match self.mode {
             Mode::Test => "--show-output",
             _ => "",
         };
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        let mut commands = match self.mode {
+            Mode::Cargo => self.cargo_test_commands().map_err(|stderr| ExerciseOutput {
+                stdout: String::new(),
+                stderr,
+                stopped: None,
+                errors: Vec::new(),
+            })?,
+            _ => {
+                let mut command = Command::new(temp_file());
+                command.arg(arg);
+                vec![command]
+            }
+        };
+
+        // Like `cargo test`, stop at the first test binary that fails
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut stopped = None;
+        let mut success = true;
+        for command in &mut commands {
+            let cmd = self
+                .limits
+                .run(command, self.stdin().as_deref())
+                .expect("Failed to run 'run' command");
+            stdout.extend(cmd.stdout);
+            stderr.extend(cmd.stderr);
+            stopped = cmd.stopped;
+            success = cmd.status.is_some_and(|status| status.success());
+            if !success {
+                break;
+            }
+        }
 
+        let mut stdout = String::from_utf8_lossy(&stdout).to_string();
+        if self.hidden_tests.is_some() {
+            stdout = hide_hidden_test_output(&stdout);
+        }
         let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            stdout,
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+            stopped,
+            errors: Vec::new(),
         };
 
-        if cmd.status.success() {
+        if success {
             Ok(output)
         } else {
             Err(output)
         }
     }
 
-    pub fn state(&self) -> State {
-        let mut source_file =
-            File::open(&self.path).expect("We were unable to open the exercise file!");
-
-        let source = {
-            let mut s = String::new();
-            source_file
-                .read_to_string(&mut s)
-                .expect("We were unable to read the exercise file!");
-            s
-        };
+    // The test binaries `cargo test` would run for a cargo exercise.
+    // They are run directly rather than through `cargo test`, so that the
+    // limits apply to them alone, and stopping one doesn't leave it running
+    // behind a killed cargo.
+    fn cargo_test_commands(&self) -> Result<Vec<Command>, String> {
+        let output = self
+            .cargo_command("test")
+            .args(["--no-run", "--message-format=json"])
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+        let crate_dir = self.path.canonicalize().map_err(|e| e.to_string())?;
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<CargoArtifact>(line).ok())
+            .filter(|artifact| artifact.profile.as_ref().is_some_and(|profile| profile.test))
+            .filter_map(|artifact| artifact.executable)
+            .map(|executable| {
+                let mut command = Command::new(executable);
+                command
+                    .arg("--show-output")
+                    .current_dir(&crate_dir)
+                    .env("CARGO_MANIFEST_DIR", &crate_dir);
+                command
+            })
+            .collect())
+    }
 
-        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+    // The file the test harness is built from: the exercise itself, or a
+    // wrapper that includes it and adds the hidden tests of the exercise
+    // as a `hidden_tests` module. The wrapper lives next to temp_file(),
+    // so the paths it includes are the ones in info.toml.
+    fn test_harness_source(&self) -> io::Result<PathBuf> {
+        let hidden_tests = match &self.hidden_tests {
+            Some(hidden_tests) => hidden_tests,
+            None => return Ok(self.path.clone()),
+        };
+        let wrapper = PathBuf::from(format!("{}_harness.rs", temp_file()));
+        fs::write(
+            &wrapper,
+            format!(
+                "include!({:?});\n\n#[cfg(test)]\n#[path = {:?}]\nmod hidden_tests;\n",
+                self.path.to_string_lossy(),
+                hidden_tests.to_string_lossy()
+            ),
+        )?;
+        Ok(wrapper)
+    }
 
-        if !re.is_match(&source) {
-            return State::Done;
+    // Lint the exercise with Clippy in a throwaway crate whose binary is the
+    // exercise file itself. The crate is new every time, so there's nothing
+    // cached that could hide lints, and exercises can be linted in parallel.
+    fn clippy(&self) -> io::Result<process::Output> {
+        let crate_dir = clippy_crate_dir();
+        let exercise_path = self.path.canonicalize()?;
+        let cargo_toml = format!(
+            r#"[package]
+name = "{}"
+version = "0.0.1"
+edition = "{}"
+[[bin]]
+name = "{}"
+path = {:?}
+"#,
+            self.name.to_lowercase(),
+            self.build.edition(),
+            self.name,
+            exercise_path.to_string_lossy()
+        );
+        fs::create_dir_all(&crate_dir)?;
+        fs::write(crate_dir.join("Cargo.toml"), cargo_toml)?;
+
+        let mut lint_args = vec!["-D", "warnings"];
+        for lint in &self.lints {
+            lint_args.extend(["-D", lint]);
         }
+        let output = Command::new("cargo")
+            .args(["clippy", "--offline", "--manifest-path"])
+            .arg(crate_dir.join("Cargo.toml"))
+            .env("CARGO_ENCODED_RUSTFLAGS", self.build.flags().join("\x1f"))
+            .args(RUSTC_COLOR_ARGS)
+            .arg("--")
+            .args(lint_args)
+            .output();
+        let _ignored = fs::remove_dir_all(&crate_dir);
+        output
+    }
 
-        let matched_line_index = source
-            .lines()
-            .enumerate()
-            .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
-            .expect("This should not happen at all");
+    // A cargo command for the crate of a cargo exercise, which only uses the
+    // vendored registry of the course to resolve dependencies
+    fn cargo_command(&self, subcommand: &str) -> Command {
+        let mut command = Command::new("cargo");
+        command
+            .env("CARGO_ENCODED_RUSTFLAGS", self.build.flags().join("\x1f"))
+            .arg(subcommand)
+            .arg("--manifest-path")
+            .arg(self.path.join("Cargo.toml"))
+            .args(["--target-dir", CARGO_TARGET_DIR_PATH, "--offline"]);
+        if let Ok(vendor_dir) = Path::new(VENDOR_DIR_PATH).canonicalize() {
+            command
+                .arg("--config")
+                .arg("source.crates-io.replace-with=\"vendored-sources\"")
+                .arg("--config")
+                .arg(format!("source.vendored-sources.directory={:?}", vendor_dir.to_string_lossy()));
+        }
+        command
+    }
 
-        let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
-        let max_line = matched_line_index + CONTEXT;
+    // The source files of the exercise: every Rust file of the crate of a
+    // cargo exercise, the exercise file itself otherwise
+    pub fn source_files(&self) -> Vec<PathBuf> {
+        match self.mode {
+            Mode::Cargo => crate_files(&self.path)
+                .into_iter()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+                .collect(),
+            _ => vec![self.path.clone()],
+        }
+    }
 
-        let context = source
-            .lines()
-            .enumerate()
-            .filter(|&(i, _)| i >= min_line && i <= max_line)
-            .map(|(i, line)| ContextLine {
-                line: line.to_string(),
-                number: i + 1,
-                important: i == matched_line_index,
-            })
-            .collect();
+    // Whether the file is part of the exercise
+    pub fn contains_file(&self, file: &Path) -> bool {
+        match self.mode {
+            Mode::Cargo => self.path.canonicalize().is_ok_and(|dir| file.starts_with(dir)),
+            _ => file.ends_with(&self.path),
+        }
+    }
 
-        State::Pending(context)
+    // The exercise is pending as long as any of its source files has the marker
+    pub fn state(&self) -> State {
+        self.source_files()
+            .iter()
+            .map(|path| file_state(path))
+            .find(|state| *state != State::Done)
+            .unwrap_or(State::Done)
     }
 
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
     // without actually having solved anything.
-    // The only other way to truly check this would to compile and run
-    // the exercise; which would be both costly and counterintuitive
+    // Combine it with the verification record in `Progress::is_done`
+    // to know whether the exercise was actually solved.
+    // Exercises whose file is missing are never done.
     pub fn looks_done(&self) -> bool {
-        self.state() == State::Done
+        self.path.exists() && self.state() == State::Done
+    }
+
+    // The levels of the exercise's hint, from the gentlest nudge to the near-answer.
+    // An exercise with a single `hint` has one level.
+    pub fn hint_levels(&self) -> Vec<&str> {
+        if self.hints.is_empty() {
+            vec![self.hint.as_str()]
+        } else {
+            self.hints.iter().map(String::as_str).collect()
+        }
+    }
+
+    // The contents of the hidden tests of the exercise, empty if it has none
+    pub fn hidden_tests_source(&self) -> Vec<u8> {
+        self.hidden_tests
+            .as_ref()
+            .and_then(|hidden_tests| fs::read(hidden_tests).ok())
+            .unwrap_or_default()
+    }
+
+    // The input the binary of the exercise is run with, if any
+    pub fn stdin(&self) -> Option<String> {
+        self.stdin
+            .clone()
+            .or_else(|| fs::read_to_string(self.path.with_extension("stdin")).ok())
+    }
+
+    // What the binary of the exercise must print, if that is checked
+    pub fn expected_output(&self) -> Option<ExpectedOutput> {
+        if let Some(regex) = &self.expected_stdout_regex {
+            return Some(ExpectedOutput::Regex(regex.clone()));
+        }
+        self.expected_stdout
+            .clone()
+            .or_else(|| fs::read_to_string(self.path.with_extension("stdout")).ok())
+            .map(ExpectedOutput::Exact)
+    }
+
+    // Why the expected_stdout_regex of the exercise isn't a valid regex, if it isn't
+    pub fn regex_error(&self) -> Option<String> {
+        let regex = self.expected_stdout_regex.as_ref()?;
+        Regex::new(regex).err().map(|e| e.to_string())
+    }
+
+    // Whether a compilation failed with exactly the expected error codes
+    pub fn fails_as_expected(&self, errors: &[CompileError]) -> bool {
+        let mut codes: Vec<&str> = errors.iter().map(|error| error.code.as_str()).collect();
+        let mut expected: Vec<&str> = self.expected_errors.iter().map(String::as_str).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        expected.sort_unstable();
+        expected.dedup();
+        codes == expected
+    }
+
+    // The hint for the first of the given compiler errors that has one
+    pub fn error_hint(&self, errors: &[CompileError]) -> Option<(&str, &str)> {
+        errors.iter().find_map(|error| {
+            self.hints_by_error
+                .get_key_value(&error.code)
+                .map(|(code, hint)| (code.as_str(), hint.as_str()))
+        })
     }
 }
 
@@ -254,24 +720,119 @@ impl Display for Exercise {
     }
 }
 
+// The state of a single source file, pending if it still has the marker
+fn file_state(path: &Path) -> State {
+    let mut source_file = File::open(path).expect("We were unable to open the exercise file!");
+
+    let source = {
+        let mut s = String::new();
+        source_file
+            .read_to_string(&mut s)
+            .expect("We were unable to read the exercise file!");
+        s
+    };
+
+    let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+
+    if !re.is_match(&source) {
+        return State::Done;
+    }
+
+    let matched_line_index = source
+        .lines()
+        .enumerate()
+        .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
+        .expect("This should not happen at all");
+
+    let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
+    let max_line = matched_line_index + CONTEXT;
+
+    let context = source
+        .lines()
+        .enumerate()
+        .filter(|&(i, _)| i >= min_line && i <= max_line)
+        .map(|(i, line)| ContextLine {
+            line: line.to_string(),
+            number: i + 1,
+            important: i == matched_line_index,
+        })
+        .collect();
+
+    State::Pending(context)
+}
+
+// The path without its `.` components, so that `./homeworks/x.rs`
+// and `homeworks/x.rs` name the same exercise
+pub fn normalize_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+// Every file of a crate, in a stable order, without its build output and lock file
+pub fn crate_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return files,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        if name == "target" || name == "Cargo.lock" || name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            files.extend(crate_files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+// Drop what the hidden tests printed from the output of a test harness,
+// like the values their assertions compared, keeping only their names
+fn hide_hidden_test_output(stdout: &str) -> String {
+    let mut shown = String::new();
+    let mut hiding = false;
+    for line in stdout.lines() {
+        if line.starts_with("---- ") && line.ends_with(" ----") {
+            hiding = line.starts_with("---- hidden_tests::");
+            if hiding {
+                shown.push_str(line);
+                shown.push_str("\n(the output of hidden tests is not shown)\n\n");
+                continue;
+            }
+        } else if line == "failures:" || line == "successes:" {
+            hiding = false;
+        }
+        if !hiding {
+            shown.push_str(line);
+            shown.push('\n');
+        }
+    }
+    shown
+}
+
 #[inline]
 fn clean() {
-    let _ignored = remove_file(&temp_file());
+    let _ignored = remove_file(temp_file());
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
+        File::create(temp_file()).unwrap();
         let exercise = Exercise {
             name: String::from("example"),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::from(""),
+            ..Default::default()
         };
         let compiled = exercise.compile().unwrap();
         drop(compiled);
@@ -284,7 +845,7 @@ mod test {
             name: "pending_exercise".into(),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         let state = exercise.state();
@@ -325,21 +886,94 @@ mod test {
             name: "finished_exercise".into(),
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
             mode: Mode::Compile,
-            hint: String::new(),
+            ..Default::default()
         };
 
         assert_eq!(exercise.state(), State::Done);
     }
 
+    #[test]
+    fn test_cargo_exercise_source_files() {
+        let exercise = Exercise {
+            name: "hello".into(),
+            path: PathBuf::from("tests/fixture/cargo/hello"),
+            mode: Mode::Cargo,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            exercise.source_files(),
+            vec![PathBuf::from("tests/fixture/cargo/hello/src/lib.rs")]
+        );
+        assert_eq!(exercise.state(), State::Done);
+        assert!(exercise.contains_file(&Path::new("tests/fixture/cargo/hello/src/lib.rs").canonicalize().unwrap()));
+    }
+
     #[test]
     fn test_exercise_with_output() {
         let exercise = Exercise {
             name: "exercise_with_output".into(),
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
             mode: Mode::Test,
-            hint: String::new(),
+            ..Default::default()
         };
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_expected_output_regex_matches_whole_output() {
+        let expected = ExpectedOutput::Regex(String::from(r"\d+"));
+        assert!(expected.matches("42\n"));
+        assert!(!expected.matches("x42\n"));
+        assert!(!expected.matches("42\n42\n"));
+    }
+
+    #[test]
+    fn test_build_settings_defaults() {
+        let defaults = BuildSettings {
+            edition: Some("2018".into()),
+            rustc_flags: Some(vec!["--cfg".into(), "course".into()]),
+            deny_warnings: Some(true),
+        };
+        let settings = BuildSettings {
+            edition: None,
+            rustc_flags: Some(Vec::new()),
+            deny_warnings: Some(false),
+        }
+        .or(&defaults);
+        assert_eq!(settings.rustc_args(), vec!["--edition", "2018"]);
+        assert_eq!(
+            BuildSettings::default().or(&defaults).rustc_args(),
+            vec!["--edition", "2018", "--cfg", "course", "-D", "warnings"]
+        );
+        assert_eq!(BuildSettings::default().rustc_args(), vec!["--edition", "2021"]);
+    }
+
+    #[test]
+    fn test_hide_hidden_test_output() {
+        let stdout = "running 2 tests
+test hidden_tests::negative ... FAILED
+test tests::two ... ok
+
+failures:
+
+---- hidden_tests::negative stdout ----
+assertion `left == right` failed
+  left: -3
+ right: -6
+
+---- tests::other stdout ----
+shown
+
+failures:
+    hidden_tests::negative
+";
+        let shown = hide_hidden_test_output(stdout);
+        assert!(shown.contains("test hidden_tests::negative ... FAILED"));
+        assert!(shown.contains("(the output of hidden tests is not shown)"));
+        assert!(!shown.contains("right: -6"));
+        assert!(shown.contains("---- tests::other stdout ----\nshown\n"));
+        assert!(shown.ends_with("failures:\n    hidden_tests::negative\n"));
+    }
 }
@@ -0,0 +1,79 @@
+use crate::diagnostics::CompileError;
+use crate::exercise::Exercise;
+use std::env;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Show rustc's explanation of each of the given errors of the exercise in a
+// pager, below the line of the exercise the error points at
+pub fn explain(exercise: &Exercise, errors: &[CompileError]) -> Result<(), ()> {
+    if errors.is_empty() {
+        println!(
+            "There are no compiler errors of {} to explain. Run or verify it first!",
+            exercise
+        );
+        return Err(());
+    }
+
+    let mut text = String::new();
+    for error in errors {
+        writeln!(text, "error[{}]", error.code).unwrap();
+        if let Some(location) = &error.location {
+            writeln!(text, "  --> {}", location).unwrap();
+        }
+        if let Some(source_line) = &error.source_line {
+            writeln!(text, "   | {}", source_line).unwrap();
+        }
+        writeln!(text).unwrap();
+        match explanation(&error.code) {
+            Some(explanation) => text.push_str(&explanation),
+            None => writeln!(text, "rustc has no explanation for {}.", error.code).unwrap(),
+        }
+        writeln!(text).unwrap();
+    }
+
+    page(&text);
+    Ok(())
+}
+
+// The output of `rustc --explain` for the given error code
+fn explanation(code: &str) -> Option<String> {
+    let output = Command::new("rustc").args(["--explain", code]).output().ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+// Show the text in the student's pager, or print it when nobody is
+// watching the terminal or no pager can be started
+fn page(text: &str) {
+    if !console::user_attended() {
+        print!("{}", text);
+        return;
+    }
+
+    let pager = env::var("PAGER").unwrap_or_else(|_| String::from("less -R"));
+    let mut words = pager.split_whitespace();
+    let spawned = words
+        .next()
+        .ok_or(())
+        .and_then(|program| {
+            Command::new(program)
+                .args(words)
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_err(|_| ())
+        });
+    match spawned {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ignored = stdin.write_all(text.as_bytes());
+            }
+            let _ignored = child.wait();
+        }
+        Err(_) => print!("{}", text),
+    }
+}
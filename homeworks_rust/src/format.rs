@@ -0,0 +1,83 @@
+use crate::exercise::Mode;
+use crate::verify::{Outcome, Report};
+use console::strip_ansi_codes;
+use serde::Serialize;
+use std::path::Path;
+use std::str::FromStr;
+
+// How the results of compiling and running exercises are printed
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum OutputFormat {
+    // Colored messages and prompts meant to be read by students
+    Human,
+    // One JSON object per line, meant to be read by grading scripts
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown format '{}', expected 'human' or 'json'", s)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: &'a str,
+    path: &'a Path,
+    mode: Mode,
+    outcome: Outcome,
+    duration_ms: u64,
+    stdout: String,
+    stderr: String,
+    error_codes: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonSummary {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    total: usize,
+    passed: usize,
+    pending: usize,
+    failed: usize,
+    duration_ms: u64,
+}
+
+// Print the result of a single exercise as one line of JSON
+pub fn print_json_report(report: &Report) {
+    let json_report = JsonReport {
+        kind: "exercise",
+        name: &report.exercise.name,
+        path: &report.exercise.path,
+        mode: report.exercise.mode,
+        outcome: report.outcome,
+        duration_ms: report.duration().as_millis() as u64,
+        stdout: strip_ansi_codes(report.stdout()).into_owned(),
+        stderr: strip_ansi_codes(report.stderr()).into_owned(),
+        error_codes: report.errors().iter().map(|error| error.code.as_str()).collect(),
+    };
+    println!("{}", serde_json::to_string(&json_report).unwrap());
+}
+
+// Print the totals of the given results as one line of JSON
+pub fn print_json_summary(reports: &[Report]) {
+    let passed = reports.iter().filter(|r| r.outcome.passed()).count();
+    let pending = reports.iter().filter(|r| r.outcome == Outcome::PendingMarker).count();
+    let summary = JsonSummary {
+        kind: "summary",
+        total: reports.len(),
+        passed,
+        pending,
+        failed: reports.len() - passed - pending,
+        duration_ms: reports.iter().map(|r| r.duration().as_millis() as u64).sum(),
+    };
+    println!("{}", serde_json::to_string(&summary).unwrap());
+}
@@ -0,0 +1,196 @@
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use crate::verify::{evaluate, Outcome, RunMode};
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// What grading an exercise is based on by default
+const DEFAULT_WEIGHT: f64 = 1.0;
+const DEFAULT_HINT_PENALTY: f64 = 0.1;
+const DEFAULT_SOLUTION_PENALTY: f64 = 0.5;
+
+// How an exercise is scored
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Grading {
+    // How many points the exercise is worth
+    pub weight: Option<f64>,
+    // The share of the points taken off for every hint level revealed
+    pub hint_penalty: Option<f64>,
+    // The share of the points taken off once the reference solution was shown
+    pub solution_penalty: Option<f64>,
+}
+
+impl Grading {
+    pub fn weight(&self) -> f64 {
+        self.weight.unwrap_or(DEFAULT_WEIGHT)
+    }
+
+    // The points a passing exercise scores after the penalties for the help
+    // the student got, which never go below zero
+    pub fn score(&self, hints_used: u32, solution_shown: bool) -> f64 {
+        let mut penalty = self.hint_penalty.unwrap_or(DEFAULT_HINT_PENALTY) * f64::from(hints_used);
+        if solution_shown {
+            penalty += self.solution_penalty.unwrap_or(DEFAULT_SOLUTION_PENALTY);
+        }
+        round(self.weight() * (1.0 - penalty).max(0.0))
+    }
+}
+
+// The grade of a single exercise
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExerciseGrade {
+    pub name: String,
+    pub homework: Option<u32>,
+    pub outcome: Outcome,
+    pub weight: f64,
+    pub hints_used: u32,
+    pub solution_shown: bool,
+    pub score: f64,
+}
+
+// The grades of all exercises of a homework, or of the whole course
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GradeReport {
+    pub homework: Option<u32>,
+    pub exercises: Vec<ExerciseGrade>,
+    pub score: f64,
+    pub max_score: f64,
+}
+
+// Verify every exercise without prompting or stopping at failures, and score
+// it. The "I AM NOT DONE" marker is ignored, and the hints and solutions in
+// the progress record are taken off the score. The progress record is left as is.
+// The cache is never used: it lives in the checkout of the student, who could
+// have planted a passing result in it.
+pub fn grade(exercises: &[&Exercise], homework: Option<u32>, progress: &Progress) -> GradeReport {
+    let mut grades = Vec::new();
+    for exercise in exercises {
+        let progress_bar = ProgressBar::new_spinner();
+        progress_bar.set_message(format!("Grading {}...", exercise).as_str());
+        progress_bar.enable_steady_tick(100);
+        grades.push(grade_exercise(exercise, progress));
+        progress_bar.finish_and_clear();
+    }
+
+    GradeReport {
+        homework,
+        score: round(grades.iter().map(|grade| grade.score).sum()),
+        max_score: round(grades.iter().map(|grade| grade.weight).sum()),
+        exercises: grades,
+    }
+}
+
+fn grade_exercise(exercise: &Exercise, progress: &Progress) -> ExerciseGrade {
+    // Passing with the marker still in place is a pass when grading
+    let outcome = match evaluate(exercise, RunMode::Interactive, None).outcome {
+        Outcome::PendingMarker => Outcome::Compiled,
+        outcome => outcome,
+    };
+    let hints_used = progress.hints_used(exercise);
+    let solution_shown = progress.solution_shown(exercise);
+    let score = if outcome.is_success() {
+        exercise.grading.score(hints_used, solution_shown)
+    } else {
+        0.0
+    };
+    ExerciseGrade {
+        name: exercise.name.clone(),
+        homework: exercise.homework,
+        outcome,
+        weight: exercise.grading.weight(),
+        hints_used,
+        solution_shown,
+        score,
+    }
+}
+
+// Print the grades as a table, followed by the total score
+pub fn print_table(report: &GradeReport) {
+    println!(
+        "{:<20}\t{:<22}\t{:<5}\t{:<8}\t{:>7}",
+        "Name", "Outcome", "Hints", "Solution", "Score"
+    );
+    for grade in &report.exercises {
+        println!(
+            "{:<20}\t{:<22}\t{:<5}\t{:<8}\t{:>7}",
+            grade.name,
+            grade.outcome.to_string(),
+            grade.hints_used,
+            if grade.solution_shown { "shown" } else { "-" },
+            format!("{}/{}", grade.score, grade.weight)
+        );
+    }
+    println!();
+    println!("Total score: {}/{}", report.score, report.max_score);
+}
+
+// Write the grades to the given path, as JSON if it ends in `.json`
+// and as CSV otherwise
+pub fn write_report(report: &GradeReport, path: &Path) -> io::Result<()> {
+    let contents = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::to_string_pretty(report).map_err(io::Error::other)?
+    } else {
+        to_csv(report)
+    };
+    fs::write(path, contents)
+}
+
+// One line per exercise and a last line with the total
+fn to_csv(report: &GradeReport) -> String {
+    let mut csv = String::from("name,homework,outcome,weight,hints_used,solution_shown,score\n");
+    for grade in &report.exercises {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            csv_field(&grade.name),
+            grade.homework.map(|homework| homework.to_string()).unwrap_or_default(),
+            grade.outcome,
+            grade.weight,
+            grade.hints_used,
+            grade.solution_shown,
+            grade.score
+        )
+        .unwrap();
+    }
+    writeln!(csv, "total,,,{},,,{}", report.max_score, report.score).unwrap();
+    csv
+}
+
+// Quote a CSV field if it needs to be
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Round points to two decimals, so that penalties add up as expected
+fn round(points: f64) -> f64 {
+    (points * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Overridable;
+
+    #[test]
+    fn test_score_penalties() {
+        let defaults = Grading {
+            weight: Some(2.0),
+            hint_penalty: Some(0.25),
+            solution_penalty: None,
+        };
+        let grading = Grading::default().or(&defaults);
+        assert_eq!(grading.score(0, false), 2.0);
+        assert_eq!(grading.score(1, false), 1.5);
+        assert_eq!(grading.score(1, true), 0.5);
+        assert_eq!(grading.score(4, true), 0.0);
+        assert_eq!(Grading::default().score(3, false), 0.7);
+    }
+}
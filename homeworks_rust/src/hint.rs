@@ -0,0 +1,32 @@
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use console::style;
+
+// Reveal the hint of the exercise up to the given level, or one level more
+// than the student has seen so far, and save how far they got
+pub fn hint(exercise: &Exercise, level: Option<u32>, progress: &mut Progress) {
+    let levels = exercise.hint_levels().len() as u32;
+    let level = level
+        .unwrap_or_else(|| progress.hints_used(exercise) + 1)
+        .clamp(1, levels);
+    progress.record_hints_used(exercise, level);
+    progress.save();
+    print_hints(exercise, level);
+}
+
+// Print the first `revealed` levels of the exercise's hint
+pub fn print_hints(exercise: &Exercise, revealed: u32) {
+    let levels = exercise.hint_levels();
+    if levels.len() == 1 {
+        println!("{}", levels[0]);
+        return;
+    }
+
+    for (number, level) in levels.iter().enumerate().take(revealed as usize) {
+        println!("{} {}", style(format!("Hint {}/{}:", number + 1, levels.len())).bold(), level);
+    }
+    let remaining = levels.len().saturating_sub(revealed as usize);
+    if remaining > 0 {
+        println!("Ask for the hint again to reveal {} more level(s).", remaining);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::exercise::Exercise;
+use crate::verify::{Outcome, Report};
+use console::strip_ansi_codes;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+// Write the results of a verification as a JUnit XML report,
+// with one testcase per exercise.
+// Exercises that were never reached are reported as skipped.
+pub fn write_report(path: &Path, reports: &[Report], skipped: &[&Exercise]) {
+    if let Err(e) = fs::write(path, render(reports, skipped)) {
+        println!("Failed to write the JUnit report to {}: {}", path.display(), e);
+    }
+}
+
+fn render(reports: &[Report], skipped: &[&Exercise]) -> String {
+    let failures = reports.iter().filter(|r| !r.outcome.passed()).count();
+    let total_time: Duration = reports.iter().map(|r| r.duration()).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuites>\n  <testsuite name=\"rustlings\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{:.3}\">",
+        reports.len() + skipped.len(),
+        failures,
+        skipped.len(),
+        total_time.as_secs_f64()
+    )
+    .unwrap();
+
+    for report in reports {
+        let exercise = report.exercise;
+        write!(xml, "    {}", testcase_open(exercise, report.duration())).unwrap();
+
+        let body = match report.outcome {
+            Outcome::Compiled | Outcome::FailedAsExpected => None,
+            Outcome::CompileFailed | Outcome::UnexpectedErrors => Some(report.stderr().to_string()),
+            Outcome::WrongOutput => Some(format!(
+                "The exercise printed the wrong output:\n{}",
+                report.stdout()
+            )),
+            Outcome::CompiledUnexpectedly => Some(format!(
+                "The exercise compiled, but it should fail with {}.",
+                report.exercise.expected_errors.join(", ")
+            )),
+            Outcome::TestsFailed => Some(report.stdout().to_string()),
+            Outcome::RunFailed
+            | Outcome::TimedOut
+            | Outcome::ExceededMemory
+            | Outcome::ExceededOutput => Some(format!("{}\n{}", report.stdout(), report.stderr())),
+            Outcome::PendingMarker => Some(String::from(
+                "The exercise passes, but it still contains the `I AM NOT DONE` comment.",
+            )),
+            Outcome::Tampered => Some(String::from(
+                "The exercise changes a protected region of its file.",
+            )),
+        };
+        match body {
+            None => xml.push_str("/>\n"),
+            Some(body) => {
+                let outcome = report.outcome.to_string();
+                writeln!(
+                    xml,
+                    ">\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>",
+                    outcome,
+                    outcome,
+                    escape(&body)
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    for exercise in skipped {
+        writeln!(
+            xml,
+            "    {}>\n      <skipped/>\n    </testcase>",
+            testcase_open(exercise, Duration::default())
+        )
+        .unwrap();
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+// The opening tag of the testcase of an exercise, without its closing `>`
+fn testcase_open(exercise: &Exercise, time: Duration) -> String {
+    // Group exercises by the directory they live in, e.g. `homeworks.homework5.variables`
+    let classname = exercise
+        .path
+        .parent()
+        .map(|dir| dir.to_string_lossy().replace(['/', '\\'], "."))
+        .filter(|classname| !classname.is_empty())
+        .unwrap_or_else(|| String::from("rustlings"));
+    format!(
+        "<testcase name=\"{}\" classname=\"{}\" file=\"{}\" time=\"{:.3}\"",
+        escape(&exercise.name),
+        escape(&classname),
+        escape(&exercise.path.to_string_lossy()),
+        time.as_secs_f64()
+    )
+}
+
+// Escape text for use in XML content and attributes,
+// dropping the color codes and control characters XML can't represent
+pub fn escape(text: &str) -> String {
+    strip_ansi_codes(text)
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+        .fold(String::new(), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("\u{1b}[1merror\u{1b}[0m: expected `<T>` & \"found\""),
+            "error: expected `&lt;T&gt;` &amp; &quot;found&quot;"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long a compiled exercise may run by default
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Limits on the resources a compiled exercise may use while running
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Limits {
+    // How many seconds the binary may run before it is stopped
+    pub timeout_secs: Option<u64>,
+    // How much memory the binary may allocate, in megabytes (Linux only)
+    pub memory_limit_mb: Option<u64>,
+    // How much the binary may print to stdout and stderr, in kilobytes
+    pub output_limit_kb: Option<u64>,
+}
+
+// Why a binary was stopped before it exited on its own
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Stopped {
+    // It ran for longer than its timeout
+    TimedOut,
+    // It tried to allocate more memory than its limit
+    ExceededMemory,
+    // It printed more than its output limit
+    ExceededOutput,
+}
+
+// Everything a binary left behind once it exited or was stopped
+pub struct LimitedOutput {
+    pub status: Option<ExitStatus>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub stopped: Option<Stopped>,
+}
+
+impl Limits {
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    // Run the command to completion with the given input,
+    // stopping it as soon as it exceeds a limit
+    pub fn run(&self, command: &mut Command, stdin: Option<&str>) -> std::io::Result<LimitedOutput> {
+        let stdin_config = if stdin.is_some() { Stdio::piped() } else { Stdio::null() };
+        command.stdin(stdin_config).stdout(Stdio::piped()).stderr(Stdio::piped());
+        #[cfg(target_os = "linux")]
+        if let Some(memory_limit_mb) = self.memory_limit_mb {
+            set_memory_limit(command, memory_limit_mb * 1024 * 1024);
+        }
+        let mut child = command.spawn()?;
+
+        if let (Some(mut child_stdin), Some(input)) = (child.stdin.take(), stdin) {
+            // Write on its own thread so a binary that doesn't read its input can't block us
+            let input = input.to_string();
+            thread::spawn(move || {
+                let _ignored = child_stdin.write_all(input.as_bytes());
+            });
+        }
+
+        let output_limit = self.output_limit_kb.map(|kb| kb as usize * 1024);
+        let exceeded_output = Arc::new(AtomicBool::new(false));
+        let stdout = read_limited(child.stdout.take().unwrap(), output_limit, &exceeded_output);
+        let stderr = read_limited(child.stderr.take().unwrap(), output_limit, &exceeded_output);
+
+        let deadline = Instant::now() + self.timeout();
+        let mut stopped = None;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if exceeded_output.load(Ordering::SeqCst) {
+                stopped = Some(Stopped::ExceededOutput);
+            } else if Instant::now() >= deadline {
+                stopped = Some(Stopped::TimedOut);
+            }
+            if stopped.is_some() {
+                let _ignored = child.kill();
+                let _ignored = child.wait();
+                break None;
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+        if exceeded_output.load(Ordering::SeqCst) {
+            stopped = Some(Stopped::ExceededOutput);
+        }
+        // Rust binaries abort with this message when an allocation fails
+        let out_of_memory = String::from_utf8_lossy(&stderr).contains("memory allocation of");
+        if stopped.is_none() && self.memory_limit_mb.is_some() && out_of_memory {
+            stopped = Some(Stopped::ExceededMemory);
+        }
+
+        Ok(LimitedOutput {
+            status,
+            stdout,
+            stderr,
+            stopped,
+        })
+    }
+}
+
+// Read the stream on its own thread, keeping at most `limit` bytes of it
+fn read_limited(
+    mut stream: impl Read + Send + 'static,
+    limit: Option<usize>,
+    exceeded: &Arc<AtomicBool>,
+) -> thread::JoinHandle<Vec<u8>> {
+    let exceeded = Arc::clone(exceeded);
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let mut buffer = [0; 8192];
+        while let Ok(read) = stream.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            match limit {
+                Some(limit) if kept.len() + read > limit => {
+                    kept.extend_from_slice(&buffer[..limit - kept.len()]);
+                    exceeded.store(true, Ordering::SeqCst);
+                    break;
+                }
+                _ => kept.extend_from_slice(&buffer[..read]),
+            }
+        }
+        kept
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn set_memory_limit(command: &mut Command, bytes: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let limit = libc::rlimit {
+        rlim_cur: bytes as libc::rlim_t,
+        rlim_max: bytes as libc::rlim_t,
+    };
+    // Safety: setrlimit is async-signal-safe, so it may be called between fork and exec
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(std::io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Overridable;
+
+    #[test]
+    fn test_timeout() {
+        let limits = Limits {
+            timeout_secs: Some(0),
+            ..Limits::default()
+        };
+        let output = limits.run(Command::new("sleep").arg("5"), None).unwrap();
+        assert_eq!(output.stopped, Some(Stopped::TimedOut));
+        assert!(output.status.is_none());
+    }
+
+    #[test]
+    fn test_output_limit() {
+        let limits = Limits {
+            output_limit_kb: Some(1),
+            ..Limits::default()
+        };
+        let output = limits.run(Command::new("yes").arg("rustlings"), None).unwrap();
+        assert_eq!(output.stopped, Some(Stopped::ExceededOutput));
+        assert_eq!(output.stdout.len(), 1024);
+    }
+
+    #[test]
+    fn test_stdin() {
+        let output = Limits::default().run(&mut Command::new("cat"), Some("input\n")).unwrap();
+        assert_eq!(output.stdout, b"input\n");
+        assert!(output.status.is_some_and(|status| status.success()));
+    }
+
+    #[test]
+    fn test_limits_defaults() {
+        let exercise_limits = Limits {
+            timeout_secs: Some(2),
+            ..Limits::default()
+        };
+        let defaults = Limits {
+            timeout_secs: Some(10),
+            memory_limit_mb: Some(256),
+            output_limit_kb: None,
+        };
+        assert_eq!(
+            exercise_limits.or(&defaults),
+            Limits {
+                timeout_secs: Some(2),
+                memory_limit_mb: Some(256),
+                output_limit_kb: None,
+            }
+        );
+    }
+}
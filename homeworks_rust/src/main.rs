@@ -1,14 +1,27 @@
+use crate::cache::Cache;
+use crate::certificate::{certify, verify_certificate};
+use crate::class::grade_class;
+use crate::course::check_course;
+use crate::diagnostics::CompileError;
 use crate::exercise::{Exercise, ExerciseList};
+use crate::explain::explain;
+use crate::format::OutputFormat;
+use crate::grade::{grade, print_table, write_report};
+use crate::hint::{hint, print_hints};
+use crate::progress::Progress;
+use crate::reset::reset;
 use crate::run::run;
-use crate::verify::verify;
+use crate::solution::solution;
+use crate::verify::{verify, Failure, VerifyOptions};
 use argh::FromArgs;
-use console::Emoji;
+use console::{style, Emoji};
 use notify::DebouncedEvent;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::ffi::OsStr;
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, RecvTimeoutError};
@@ -19,8 +32,24 @@ use std::time::Duration;
 #[macro_use]
 mod ui;
 
+mod cache;
+mod certificate;
+mod class;
+mod course;
+mod diagnostics;
+mod diff;
 mod exercise;
+mod explain;
+mod format;
+mod grade;
+mod hint;
+mod junit;
+mod limits;
+mod progress;
+mod protected;
+mod reset;
 mod run;
+mod solution;
 mod verify;
 
 // In sync with crate version
@@ -35,6 +64,9 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// always recompile exercises instead of reusing cached builds
+    #[argh(switch)]
+    no_cache: bool,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -46,12 +78,33 @@ enum Subcommands {
     Run(RunArgs),
     Hint(HintArgs),    
     Homework(HomeworkArgs),
+    Watch(WatchArgs),
+    List(ListArgs),
+    Cache(CacheArgs),
+    Reset(ResetArgs),
+    Solution(SolutionArgs),
+    Explain(ExplainArgs),
+    CheckCourse(CheckCourseArgs),
+    Grade(GradeArgs),
+    GradeClass(GradeClassArgs),
+    Certify(CertifyArgs),
+    VerifyCertificate(VerifyCertificateArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(option, default = "OutputFormat::Human")]
+    /// the output format, either "human" or "json"
+    format: OutputFormat,
+    #[argh(option)]
+    /// write a JUnit XML report of the results to the given path
+    junit: Option<PathBuf>,
+    #[argh(option, short = 'j', default = "1")]
+    /// how many exercises to compile and run at the same time
+    jobs: usize,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "run")]
@@ -60,16 +113,37 @@ struct RunArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(option, default = "OutputFormat::Human")]
+    /// the output format, either "human" or "json"
+    format: OutputFormat,
 }
 
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "homework")]
-/// Runs/Tests a single exercise
+/// Reruns the exercises of a single homework each time a file changes
 struct HomeworkArgs {
     #[argh(positional)]
-    /// the day of the homework
+    /// the number of the homework, or "list" to show every homework
     name: String,
+    #[argh(option, default = "OutputFormat::Human")]
+    /// the output format, either "human" or "json"
+    format: OutputFormat,
+    #[argh(option)]
+    /// write a JUnit XML report of the results to the given path
+    junit: Option<PathBuf>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "watch")]
+/// Reruns all exercises of the course each time a file changes
+struct WatchArgs {
+    #[argh(option, default = "OutputFormat::Human")]
+    /// the output format, either "human" or "json"
+    format: OutputFormat,
+    #[argh(option)]
+    /// write a JUnit XML report of the results to the given path
+    junit: Option<PathBuf>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -79,8 +153,137 @@ struct HintArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(option)]
+    /// reveal the hint up to the given level instead of one more level
+    level: Option<u32>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "reset")]
+/// Restores an exercise to its original state, after backing up your version
+struct ResetArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "solution")]
+/// Shows how your version of an exercise differs from its reference solution
+struct SolutionArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "explain")]
+/// Explains the compiler errors of the last failed compilation of an exercise
+struct ExplainArgs {
+    #[argh(positional)]
+    /// the name of the exercise, the next pending one if not given
+    name: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "grade")]
+/// Scores every exercise, or those of a single homework, for the teaching assistants
+struct GradeArgs {
+    #[argh(positional)]
+    /// the number of the homework to grade, every homework if not given
+    homework: Option<u32>,
+    #[argh(option, short = 'o', default = "PathBuf::from(\"grades.csv\")")]
+    /// where to write the grades, as JSON if the path ends in .json and as CSV otherwise
+    output: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "grade-class")]
+/// Grades a directory of student checkouts or tarballs, for the instructors
+struct GradeClassArgs {
+    #[argh(positional)]
+    /// the directory holding a checkout or tarball of every student
+    submissions: PathBuf,
+    #[argh(option, short = 'w')]
+    /// grade only the exercises of the given homework number
+    homework: Option<u32>,
+    #[argh(option, short = 'o', default = "PathBuf::from(\"class.csv\")")]
+    /// where to write the grades of every student as CSV
+    output: PathBuf,
+    #[argh(option, default = "PathBuf::from(\"class.html\")")]
+    /// where to write the HTML summary of the class
+    html: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "certify")]
+/// Verifies a homework and writes a certificate of it signed with your Solana keypair
+struct CertifyArgs {
+    #[argh(positional)]
+    /// the number of the homework to certify
+    homework: u32,
+    #[argh(option, short = 'k')]
+    /// the Solana keypair file to sign the certificate with
+    keypair: PathBuf,
+    #[argh(option, short = 'o')]
+    /// where to write the certificate, certificate-<homework>.json by default
+    output: Option<PathBuf>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "verify-certificate")]
+/// Checks the signature of a homework certificate, for the instructors
+struct VerifyCertificateArgs {
+    #[argh(positional)]
+    /// the certificate to check
+    certificate: PathBuf,
+    #[argh(option)]
+    /// the base58 public key the certificate must be signed by
+    public_key: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-course")]
+/// Checks that info.toml agrees with the exercise files, for course maintainers
+struct CheckCourseArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "list")]
+/// Lists the exercises available in Rustlings
+struct ListArgs {
+    #[argh(switch, short = 's')]
+    /// display only exercises that have been solved
+    solved: bool,
+    #[argh(switch, short = 'u')]
+    /// display only exercises not yet solved
+    unsolved: bool,
+    #[argh(option, short = 'w')]
+    /// display only exercises belonging to the given homework number
+    homework: Option<u32>,
+    #[argh(option, short = 'f')]
+    /// display only exercises whose name contains the given string
+    filter: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "cache")]
+/// Manages the cache of compiled exercises
+struct CacheArgs {
+    #[argh(subcommand)]
+    nested: CacheSubcommands,
 }
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum CacheSubcommands {
+    Clean(CacheCleanArgs),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "clean")]
+/// Removes every cached build
+struct CacheCleanArgs {}
+
 fn main() {
     
     let args: Args = argh::from_env();    
@@ -112,73 +315,305 @@ fn main() {
 
     // Gets homework structs
     let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().into_exercises();
     let verbose = args.nocapture;
+    let mut progress = Progress::load();
+    let cache = if args.no_cache { None } else { Cache::open() };
 
     let command = args.nested.unwrap_or_else(|| {
         println!("{}\n", DEFAULT_OUT);
         std::process::exit(0);
     });
 
-    println!("\n\nEND\n\n");    
+    // A broken regex would fail the exercise for every student, so it's
+    // reported up front. check-course lists it with the other problems.
+    if !matches!(command, Subcommands::CheckCourse(_)) {
+        if let Some((exercise, error)) = exercises.iter().find_map(|e| Some((e, e.regex_error()?))) {
+            println!("The expected_stdout_regex of {} in info.toml is not a valid regex:", exercise.name);
+            println!("{}", error);
+            std::process::exit(1);
+        }
+    }
 
     match command {  
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            let exercise = find_exercise(&subargs.name, &exercises, &progress);
+            run(exercise, verbose, subargs.format, cache.as_ref(), &mut progress)
+                .unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-            println!("{}", exercise.hint);
+            let exercise = find_exercise(&subargs.name, &exercises, &progress);
+            hint(exercise, subargs.level, &mut progress);
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, verbose).unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::Reset(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises, &progress);
+            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
         }
-        
-        Subcommands::Homework(subargs) => match homework(&exercises, verbose, subargs.name) {
-            Err(e) => {
-                println!("Error: Could not watch your progress. Error message was {:?}.", e);
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+
+        Subcommands::Explain(subargs) => {
+            let name = subargs.name.as_deref().unwrap_or("next");
+            let exercise = find_exercise(name, &exercises, &progress);
+            explain(exercise, progress.last_errors(exercise)).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Solution(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises, &progress);
+            solution(exercise, &mut progress).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Grade(subargs) => {
+            let graded: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| subargs.homework.is_none() || e.homework == subargs.homework)
+                .collect();
+            if graded.is_empty() {
+                println!("There are no exercises to grade.");
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!("{emoji} All exercises completed! {emoji}", emoji = Emoji("🎉", "★"));
-                println!("\n{}\n", FENISH_LINE);
+            let report = grade(&graded, subargs.homework, &progress);
+            print_table(&report);
+            if let Err(e) = write_report(&report, &subargs.output) {
+                println!("Failed to write the grades to {}: {}", subargs.output.display(), e);
+                std::process::exit(1);
             }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+            println!("Wrote the grades to {}", subargs.output.display());
+        }
+
+        Subcommands::GradeClass(subargs) => {
+            let graded: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| subargs.homework.is_none() || e.homework == subargs.homework)
+                .collect();
+            grade_class(&graded, &subargs.submissions, subargs.homework, &subargs.output, &subargs.html)
+                .unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::Certify(subargs) => {
+            let certified: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| e.homework == Some(subargs.homework))
+                .collect();
+            if certified.is_empty() {
+                println!("There are no exercises in homework {}.", subargs.homework);
+                std::process::exit(1);
             }
-        },   
+            let output = subargs
+                .output
+                .unwrap_or_else(|| PathBuf::from(format!("certificate-{}.json", subargs.homework)));
+            certify(&certified, subargs.homework, &subargs.keypair, &output)
+                .unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::VerifyCertificate(subargs) => {
+            verify_certificate(&exercises, &subargs.certificate, subargs.public_key.as_deref())
+                .unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::CheckCourse(_) => {
+            check_course(&exercises, Path::new("homeworks")).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::List(subargs) => {
+            let filter = subargs.filter.unwrap_or_default().to_lowercase();
+
+            let mut exercises_done: u16 = 0;
+            let mut exercises_listed: u16 = 0;
+            println!(
+                "{:<17}\t{:<46}\t{:<8}\t{:<7}\t{:<8}",
+                "Name", "Path", "Mode", "Status", "Progress"
+            );
+            for exercise in &exercises {
+                if subargs.homework.is_some() && exercise.homework != subargs.homework {
+                    continue;
+                }
+                if !exercise.name.to_lowercase().contains(&filter) {
+                    continue;
+                }
+
+                let done = progress.is_done(exercise);
+                if done {
+                    exercises_done += 1;
+                }
+                exercises_listed += 1;
+                if (subargs.solved && !done) || (subargs.unsolved && done) {
+                    continue;
+                }
+
+                let status = if done { "Done" } else { "Pending" };
+                println!(
+                    "{:<17}\t{:<46}\t{:<8}\t{:<7}\t{:<8}",
+                    exercise.name,
+                    exercise.path.display(),
+                    exercise.mode.to_string(),
+                    status,
+                    progress.state(exercise).to_string()
+                );
+            }
+
+            let percentage_progress = if exercises_listed == 0 {
+                0.0
+            } else {
+                exercises_done as f32 / exercises_listed as f32 * 100.0
+            };
+            println!(
+                "Progress: You completed {} / {} exercises ({:.2} %).",
+                exercises_done, exercises_listed, percentage_progress
+            );
+        }
+
+        Subcommands::Cache(subargs) => match subargs.nested {
+            CacheSubcommands::Clean(_) => {
+                if let Err(e) = Cache::clean() {
+                    println!("Failed to remove the cache: {}", e);
+                    std::process::exit(1);
+                }
+                println!("Removed all cached builds.");
+            }
+        },
+
+        Subcommands::Verify(subargs) => {
+            let options = VerifyOptions {
+                verbose,
+                output_format: subargs.format,
+                junit: subargs.junit,
+                jobs: subargs.jobs,
+                cache,
+                count_failures: true,
+            };
+            verify(&exercises, &mut progress, &options).unwrap_or_else(|_| std::process::exit(1));
+        }
+        
+        Subcommands::Homework(subargs) if subargs.name == "list" => list_homeworks(&exercises, &progress),
+
+        Subcommands::Homework(subargs) => {
+            let resume_command = format!("rustlings homework {}", subargs.name);
+            let options = VerifyOptions {
+                verbose,
+                output_format: subargs.format,
+                junit: subargs.junit,
+                jobs: 1,
+                cache,
+                count_failures: false,
+            };
+            let result = homework(&exercises, subargs.name, progress, &options);
+            report_watch_status(result, &resume_command, options.output_format);
+        }
+
+        Subcommands::Watch(subargs) => {
+            let options = VerifyOptions {
+                verbose,
+                output_format: subargs.format,
+                junit: subargs.junit,
+                jobs: 1,
+                cache,
+                count_failures: false,
+            };
+            let result = watch(&exercises, progress, &options);
+            report_watch_status(result, "rustlings watch", options.output_format);
+        }
     }
 }
 
-fn spawn_watch_shell(failed_exercise_hint: &Arc<Mutex<Option<String>>>, should_quit: Arc<AtomicBool>) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+// The exercise watch mode is waiting on, shared with the watch shell
+struct FailedExercise {
+    exercise: Exercise,
+    // How many levels of its hint have been revealed
+    hints_used: u32,
+    // The compiler errors it hit
+    errors: Vec<CompileError>,
+    // Whether the hint for those errors has been shown
+    error_hint_shown: bool,
+}
+
+impl FailedExercise {
+    fn new(failure: Failure, progress: &Progress) -> Self {
+        FailedExercise {
+            exercise: failure.exercise.clone(),
+            hints_used: progress.hints_used(failure.exercise),
+            errors: failure.errors,
+            error_hint_shown: false,
+        }
+    }
+
+    // Show the hint for the error the student hit first, if the exercise has one,
+    // then one more level of its general hint each time
+    fn print_next_hint(&mut self) {
+        if !self.error_hint_shown {
+            self.error_hint_shown = true;
+            if let Some((code, hint)) = self.exercise.error_hint(&self.errors) {
+                println!("{} {}", style(format!("Hint for error[{}]:", code)).bold(), hint);
+                return;
+            }
+        }
+        let levels = self.exercise.hint_levels().len() as u32;
+        self.hints_used = (self.hints_used + 1).min(levels);
+        print_hints(&self.exercise, self.hints_used);
+    }
+}
+
+// The shell only greets and says goodbye to humans, so that the JSON output
+// of scripts driving watch mode stays one object per line
+fn spawn_watch_shell(
+    failed_exercise: &Arc<Mutex<Option<FailedExercise>>>,
+    progress: &Arc<Mutex<Progress>>,
+    should_quit: Arc<AtomicBool>,
+    output_format: OutputFormat,
+) {
+    let failed_exercise = Arc::clone(failed_exercise);
+    let progress = Arc::clone(progress);
+    let human = output_format == OutputFormat::Human;
+    if human {
+        println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+    }
     thread::spawn(move || loop {
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
+            // Nobody is left to type commands once stdin is closed
+            Ok(0) => break,
             Ok(_) => {
                 let input = input.trim();
                 if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{}", hint);
+                    if let Some(failed) = &mut *failed_exercise.lock().unwrap() {
+                        failed.print_next_hint();
+                        // Saved right away, so that quitting any other way doesn't lose it
+                        let mut progress = progress.lock().unwrap();
+                        progress.record_hints_used(&failed.exercise, failed.hints_used);
+                        progress.save();
+                    }
+                } else if input == "explain" {
+                    // Clone so the lock isn't held while the pager is open
+                    let failed = failed_exercise
+                        .lock()
+                        .unwrap()
+                        .as_ref()
+                        .map(|f| (f.exercise.clone(), f.errors.clone()));
+                    if let Some((exercise, errors)) = failed {
+                        let _ignored = explain(&exercise, &errors);
+                    }
+                } else if input == "reset" {
+                    // Clone so the lock isn't held while waiting for confirmation
+                    let exercise = failed_exercise.lock().unwrap().as_ref().map(|f| f.exercise.clone());
+                    if let Some(exercise) = exercise {
+                        let _ignored = reset(&exercise);
                     }
                 } else if input == "clear" {
                     println!("\x1B[2J\x1B[1;1H");
                 } else if input.eq("quit") {
                     should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
+                    if human {
+                        println!("Bye!");
+                    }
                 } else if input.eq("help") {
                     println!("Commands available to you in watch mode:");
-                    println!("  hint  - prints the current exercise's hint");
-                    println!("  clear - clears the screen");
-                    println!("  quit  - quits watch mode");
-                    println!("  help  - displays this help message");
+                    println!("  hint    - reveals one more level of the current exercise's hint");
+                    println!("  explain - explains the current exercise's compiler errors");
+                    println!("  reset   - restores the current exercise to its original state");
+                    println!("  clear   - clears the screen");
+                    println!("  quit    - quits watch mode");
+                    println!("  help    - displays this help message");
                     println!();
                     println!("Watch mode automatically re-evaluates the current exercise");
                     println!("when you edit a file's contents.")
@@ -191,9 +626,9 @@ fn spawn_watch_shell(failed_exercise_hint: &Arc<Mutex<Option<String>>>, should_q
     });
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise], progress: &Progress) -> &'a Exercise {
     if name.eq("next") {
-        exercises.iter().find(|e| !e.looks_done()).unwrap_or_else(|| {
+        exercises.iter().find(|e| !progress.is_done(e)).unwrap_or_else(|| {
             println!("🎉 Congratulations! You have done all the exercises!");
             println!("🔚 There are no more exercises to do next!");
             std::process::exit(1)
@@ -211,103 +646,111 @@ enum WatchStatus {
     Unfinished,
 }
 
-// Redo watch but with only the homework that has been given out
-// pass on the way here the subset
-fn homework(exercises: &[Exercise], verbose: bool, homework_number: String) -> notify::Result<WatchStatus> {
-   
-     /* Clears the terminal with an ANSI escape code.
+// Watch only the exercises of the homework that has been given out
+fn homework(
+    exercises: &[Exercise],
+    homework_number: String,
+    progress: Progress,
+    options: &VerifyOptions,
+) -> notify::Result<WatchStatus> {
+    let number = homework_number.parse::<u32>().ok();
+    let homework_exercises: Vec<Exercise> = exercises
+        .iter()
+        .filter(|e| number.is_some() && e.homework == number)
+        .cloned()
+        .collect();
+
+    if homework_exercises.is_empty() {
+        println!("No exercises found for homework '{}'!", homework_number);
+        println!("Run `rustlings homework list` to see the available homeworks.");
+        std::process::exit(1);
+    }
+
+    watch(&homework_exercises, progress, options)
+}
+
+// Print every homework of the course with its number of exercises and completion
+fn list_homeworks(exercises: &[Exercise], progress: &Progress) {
+    let mut homeworks: BTreeMap<u32, (usize, usize)> = BTreeMap::new();
+    for exercise in exercises {
+        if let Some(number) = exercise.homework {
+            let (total, done) = homeworks.entry(number).or_default();
+            *total += 1;
+            if progress.is_done(exercise) {
+                *done += 1;
+            }
+        }
+    }
+
+    println!("{:<8}\t{:<9}\t{:<4}\t{:<8}", "Homework", "Exercises", "Done", "Progress");
+    for (number, (total, done)) in homeworks {
+        let percentage_progress = done as f32 / total as f32 * 100.0;
+        println!(
+            "{:<8}\t{:<9}\t{:<4}\t{:.2} %",
+            number, total, done, percentage_progress
+        );
+    }
+}
+
+// Verify the given exercises that aren't done yet in order, then keep
+// re-verifying the pending ones whenever a file under ./homeworks changes,
+// until they are all done or the student quits from the watch shell.
+fn watch(exercises: &[Exercise], progress: Progress, options: &VerifyOptions) -> notify::Result<WatchStatus> {
+    /* Clears the terminal with an ANSI escape code.
     Works in UNIX and newer Windows terminals. */
     fn clear_screen() {
         println!("\x1Bc");
     }
 
-    println!("exercises: {:?}", exercises);
-    println!("exercise[0]: {:?}", exercises.len());    
-
     let (tx, rx) = channel();
     let should_quit = Arc::new(AtomicBool::new(false));
 
-    // load from the other dir 
     let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(2))?;
+    watcher.watch(Path::new("./homeworks"), RecursiveMode::Recursive)?;
 
-    // watch for changes anywhere in this folder (TODO check subdir check)    
-    watcher.watch(Path::new("./homeworks"), RecursiveMode::Recursive)?;  // I think watcher looks for file changes
-    clear_screen(); 
-
-    let mut homework_path: String = "./homeworks/homework".to_owned();        
-    homework_path.push_str(&homework_number);
-    
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-
-    // Check if directory exists
-    let b = Path::new(&homework_path).is_dir();   //.is_file();
-    
-    // Filter against what's in the dirctory for number 1
-    let paths = fs::read_dir(homework_path).expect("Can't find homework. Have you run the wrong homework number?");
-
-    // Vec of things that are present in this homework
-    let mut exercise_names: Vec<String> = Vec::new();
-
-    for path in paths {            
-        let strr: String = path.unwrap().path().display().to_string();       
-        let res: Vec<String> = strr.split("/").map(|s| s.to_string()).collect();     
-        exercise_names.push(res.last().unwrap().clone());
+    let human = options.output_format == OutputFormat::Human;
+    if human {
+        clear_screen();
     }
-    
-    let mut exercises_filtered: Vec<exercise::Exercise> = Vec::new();
 
-    println!("\n");
-
-    // filter out from the exercises list.
-    // Include based on matching homework subdirectories
-    for exercise in exercises {            
-        
-        let path_string: String = exercise.path.clone().into_os_string().into_string().unwrap();        
-        let path_elements: Vec<&str> = path_string.split('/').collect();       
-
-        if path_elements.len() > 3{
-
-            let exercise_dir: String = String::from(path_elements.clone()[2]);           
-
-            // retrieve 3rd variable from teh path and check for match
-            if exercise_names.contains(&exercise_dir) {
-                exercises_filtered.push(exercise.clone());
-            }
-        }
-    }    
-    
-    // println!("\nExercises in this homework set:");
-    // for exercise in exercises_filtered.clone() {           
-    //     println!("exercises_filtered: {:?}",exercise.name);
-    // }    
-
-    // pass here for looping till done
-    let failed_exercise_hint = match verify(exercises_filtered.iter(), verbose) {
+    // Shared with the watch shell, which records the hints it reveals.
+    // The locks are always taken in the order failed_exercise, then progress.
+    let progress = Arc::new(Mutex::new(progress));
+    let pending_exercises: Vec<&Exercise> = exercises
+        .iter()
+        .filter(|e| !progress.lock().unwrap().is_done(e))
+        .collect();
+    let result = verify(pending_exercises, &mut progress.lock().unwrap(), options);
+    let failed_exercise = match result {
         Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };    
-
-    println!("Spawning homeworkd watch shell");
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+        Err(failure) => Arc::new(Mutex::new(Some(FailedExercise::new(failure, &progress.lock().unwrap())))),
+    };
+    spawn_watch_shell(&failed_exercise, &progress, Arc::clone(&should_quit), options.output_format);
     loop {
         match rx.recv_timeout(Duration::from_secs(1)) {
             Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises_filtered
+                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b)
+                    if (b.extension() == Some(OsStr::new("rs")) || b.ends_with("Cargo.toml")) && b.exists() =>
+                {
+                    let filepath = b.as_path().canonicalize().unwrap();
+                    let pending_exercises: Vec<&Exercise> = {
+                        let progress = progress.lock().unwrap();
+                        exercises
                             .iter()
-                            .skip_while(|e| !filepath.ends_with(&e.path))
-                            // .filter(|e| filepath.ends_with(&e.path))
-                            .chain(exercises_filtered.iter().filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)));
+                            .skip_while(|e| !e.contains_file(&filepath))
+                            .chain(exercises.iter().filter(|e| !progress.is_done(e) && !e.contains_file(&filepath)))
+                            .collect()
+                    };
+                    if human {
                         clear_screen();
+                    }
 
-                        match verify(pending_exercises, verbose) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
+                    let result = verify(pending_exercises, &mut progress.lock().unwrap(), options);
+                    match result {
+                        Ok(_) => return Ok(WatchStatus::Finished),
+                        Err(failure) => {
+                            let mut failed_exercise = failed_exercise.lock().unwrap();
+                            *failed_exercise = Some(FailedExercise::new(failure, &progress.lock().unwrap()));
                         }
                     }
                 }
@@ -325,10 +768,33 @@ fn homework(exercises: &[Exercise], verbose: bool, homework_number: String) -> n
     }
 }
 
+// Print the outcome of a watch session and exit on watcher errors.
+// The JSON output already ends with the summary of the session.
+fn report_watch_status(result: notify::Result<WatchStatus>, resume_command: &str, output_format: OutputFormat) {
+    match result {
+        Err(e) => {
+            println!("Error: Could not watch your progress. Error message was {:?}.", e);
+            println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+            std::process::exit(1);
+        }
+        Ok(_) if output_format == OutputFormat::Json => {}
+        Ok(WatchStatus::Finished) => {
+            println!("{emoji} All exercises completed! {emoji}", emoji = Emoji("🎉", "★"));
+            println!("\n{}\n", FENISH_LINE);
+        }
+        Ok(WatchStatus::Unfinished) => {
+            println!("We hope you're enjoying learning about Rust!");
+            println!(
+                "If you want to continue working on the exercises at a later point, you can simply run `{}` again",
+                resume_command
+            );
+        }
+    }
+}
 
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
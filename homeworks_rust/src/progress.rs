@@ -0,0 +1,261 @@
+use crate::diagnostics::CompileError;
+use crate::exercise::{crate_files, Exercise};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PROGRESS_FILE_PATH: &str = ".rustlings-progress.toml";
+
+// The verification history of every exercise the student has attempted.
+// This is persisted next to info.toml so that completion no longer depends
+// only on the "I AM NOT DONE" marker.
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct Progress {
+    #[serde(default)]
+    pub exercises: BTreeMap<String, ExerciseProgress>,
+}
+
+// The record kept for a single exercise
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
+pub struct ExerciseProgress {
+    // Whether the last verification of the exercise succeeded
+    pub verified: bool,
+    // The hash of the source file at the time of the last verification
+    pub source_hash: String,
+    // The time of the last verification, in seconds since the Unix epoch
+    pub verified_at: u64,
+    // How many times the exercise has been verified
+    pub attempts: u32,
+    // How many of those verifications failed when the student asked for them
+    #[serde(default)]
+    pub failures: u32,
+    // When the reference solution was first shown, in seconds since the Unix epoch
+    #[serde(default)]
+    pub solution_unlocked_at: Option<u64>,
+    // How many levels of the exercise's hint have been revealed
+    #[serde(default)]
+    pub hints_used: u32,
+    // The errors of the most recent compilation of the exercise, none once it compiles.
+    // This is kept last, as toml needs tables after plain values.
+    #[serde(default)]
+    pub last_errors: Vec<CompileError>,
+}
+
+// What the progress record says about the current source of an exercise
+#[derive(PartialEq, Debug)]
+pub enum ProgressState {
+    // The exercise has never been verified
+    NotAttempted,
+    // The last verification of the exercise failed
+    Failed,
+    // The exercise verified successfully and hasn't been edited since
+    Verified,
+    // The exercise verified successfully but has been edited since
+    Stale,
+}
+
+impl Display for ProgressState {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let state = match self {
+            ProgressState::NotAttempted => "-",
+            ProgressState::Failed => "failed",
+            ProgressState::Verified => "verified",
+            ProgressState::Stale => "stale",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+impl Progress {
+    // Load the progress file of the current directory,
+    // starting from an empty record if there is none yet
+    pub fn load() -> Progress {
+        let path = Path::new(PROGRESS_FILE_PATH);
+        if !path.exists() {
+            return Progress::default();
+        }
+        let toml_str = fs::read_to_string(path).expect("We were unable to read the progress file!");
+        toml::from_str(&toml_str).unwrap_or_else(|e| {
+            println!("Ignoring the corrupt progress file {}: {}", PROGRESS_FILE_PATH, e);
+            Progress::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let toml_str = toml::to_string(self).expect("Failed to serialize the progress file.");
+        if let Err(e) = fs::write(PROGRESS_FILE_PATH, toml_str) {
+            println!("Failed to save your progress to {}: {}", PROGRESS_FILE_PATH, e);
+        }
+    }
+
+    // Record the outcome of verifying the exercise against its current source
+    pub fn record(&mut self, exercise: &Exercise, verified: bool) {
+        let entry = self.exercises.entry(exercise.name.clone()).or_default();
+        entry.verified = verified;
+        entry.source_hash = source_hash(&exercise.path).unwrap_or_default();
+        entry.verified_at = now();
+        entry.attempts += 1;
+    }
+
+    // Count a failed verification the student asked for towards unlocking
+    // the reference solution
+    pub fn record_failure(&mut self, exercise: &Exercise) {
+        self.exercises.entry(exercise.name.clone()).or_default().failures += 1;
+    }
+
+    // How many verifications of the exercise failed so far
+    pub fn failures(&self, exercise: &Exercise) -> u32 {
+        self.exercises.get(&exercise.name).map_or(0, |entry| entry.failures)
+    }
+
+    // Remember the errors of the latest compilation of the exercise,
+    // forgetting earlier ones once it compiles
+    pub fn record_compile_errors(&mut self, exercise: &Exercise, errors: &[CompileError]) {
+        let entry = self.exercises.entry(exercise.name.clone()).or_default();
+        entry.last_errors = errors.to_vec();
+    }
+
+    // The errors of the most recent compilation of the exercise
+    pub fn last_errors(&self, exercise: &Exercise) -> &[CompileError] {
+        self.exercises.get(&exercise.name).map_or(&[], |entry| &entry.last_errors)
+    }
+
+    // How many levels of the exercise's hint have been revealed so far
+    pub fn hints_used(&self, exercise: &Exercise) -> u32 {
+        self.exercises.get(&exercise.name).map_or(0, |entry| entry.hints_used)
+    }
+
+    // Record that the first `hints_used` levels of the exercise's hint were revealed
+    pub fn record_hints_used(&mut self, exercise: &Exercise, hints_used: u32) {
+        let entry = self.exercises.entry(exercise.name.clone()).or_default();
+        entry.hints_used = entry.hints_used.max(hints_used);
+    }
+
+    // Record that the student was shown the reference solution of the exercise,
+    // keeping the time it was first shown
+    pub fn record_solution_unlock(&mut self, exercise: &Exercise) {
+        let entry = self.exercises.entry(exercise.name.clone()).or_default();
+        entry.solution_unlocked_at.get_or_insert_with(now);
+    }
+
+    // Whether the student was ever shown the reference solution of the exercise
+    pub fn solution_shown(&self, exercise: &Exercise) -> bool {
+        self.exercises
+            .get(&exercise.name)
+            .is_some_and(|entry| entry.solution_unlocked_at.is_some())
+    }
+
+    pub fn state(&self, exercise: &Exercise) -> ProgressState {
+        match self.exercises.get(&exercise.name) {
+            None => ProgressState::NotAttempted,
+            Some(entry) if !entry.verified => ProgressState::Failed,
+            Some(entry) if source_hash(&exercise.path).as_ref() == Some(&entry.source_hash) => {
+                ProgressState::Verified
+            }
+            Some(_) => ProgressState::Stale,
+        }
+    }
+
+    // An exercise is done once its marker is gone and its current source
+    // has been verified successfully
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        exercise.looks_done() && self.state(exercise) == ProgressState::Verified
+    }
+}
+
+// The current time in seconds since the Unix epoch
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// The hex encoded SHA-256 of the file at the given path,
+// or of every file of the crate in the directory at the given path
+pub fn source_hash(path: &Path) -> Option<String> {
+    let mut hasher = Sha256::new();
+    if path.is_dir() {
+        for file in crate_files(path) {
+            let relative_path = file.strip_prefix(path).unwrap_or(&file);
+            hasher.update(relative_path.to_string_lossy().as_bytes());
+            hasher.update(fs::read(&file).ok()?);
+        }
+    } else {
+        hasher.update(fs::read(path).ok()?);
+    }
+    Some(hex(&hasher.finalize()))
+}
+
+// The bytes of a hash as lowercase hex
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use std::path::PathBuf;
+
+    fn finished_exercise() -> Exercise {
+        Exercise {
+            name: "finished_exercise".into(),
+            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
+            mode: Mode::Compile,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_verified() {
+        let exercise = finished_exercise();
+        let mut progress = Progress::default();
+        assert_eq!(progress.state(&exercise), ProgressState::NotAttempted);
+
+        progress.record(&exercise, false);
+        progress.record_failure(&exercise);
+        assert_eq!(progress.state(&exercise), ProgressState::Failed);
+        assert!(!progress.is_done(&exercise));
+
+        progress.record(&exercise, true);
+        assert_eq!(progress.state(&exercise), ProgressState::Verified);
+        assert!(progress.is_done(&exercise));
+        assert_eq!(progress.exercises["finished_exercise"].attempts, 2);
+        assert_eq!(progress.failures(&exercise), 1);
+    }
+
+    #[test]
+    fn test_edited_exercise_is_stale() {
+        let exercise = finished_exercise();
+        let mut progress = Progress::default();
+        progress.record(&exercise, true);
+        progress.exercises.get_mut("finished_exercise").unwrap().source_hash = "edited".into();
+
+        assert_eq!(progress.state(&exercise), ProgressState::Stale);
+        assert!(!progress.is_done(&exercise));
+    }
+
+    #[test]
+    fn test_progress_roundtrip() {
+        let exercise = finished_exercise();
+        let mut progress = Progress::default();
+        progress.record(&exercise, true);
+        progress.record_compile_errors(
+            &exercise,
+            &[CompileError {
+                code: String::from("E0382"),
+                location: Some(String::from("finished_exercise.rs:3:5")),
+                source_line: Some(String::from("fn main() {")),
+            }],
+        );
+
+        let toml_str = toml::to_string(&progress).unwrap();
+        let loaded: Progress = toml::from_str(&toml_str).unwrap();
+        assert_eq!(loaded.exercises, progress.exercises);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::diff::print_diff;
+use crate::exercise::{Exercise, Mode};
+use crate::progress::hex;
+use crate::reset::original_source;
+use sha2::{Digest, Sha256};
+use std::fs;
+
+// Lines that start and end a protected region of an exercise file
+const BEGIN_MARKER: &str = "BEGIN PROTECTED";
+const END_MARKER: &str = "END PROTECTED";
+// A line that protects everything below it, as older exercises have
+const BELOW_MARKER: &str = "Don't change anything below this line";
+
+// A protected region of an exercise that differs from the original
+pub struct Tampering {
+    // The region as it ships with the course, if the original is available
+    pub original: Option<String>,
+    // The region as it is now
+    pub current: String,
+}
+
+// The protected regions of the source of an exercise, joined together.
+// They are the lines given by `protected_lines` in info.toml, or else the
+// lines between the markers in the source, markers included.
+// Trailing whitespace is ignored, so editors that strip it don't count as tampering.
+pub fn protected_region(exercise: &Exercise, source: &str) -> Option<String> {
+    let lines: Vec<&str> = source.lines().map(str::trim_end).collect();
+    if let Some([first, last]) = exercise.protected_lines {
+        let region = lines
+            .iter()
+            .skip(first.saturating_sub(1))
+            .take((last + 1).saturating_sub(first.max(1)))
+            .copied()
+            .collect::<Vec<_>>();
+        return Some(region.join("\n"));
+    }
+
+    let mut region = Vec::new();
+    let mut protecting = false;
+    let mut found = false;
+    for line in lines {
+        if line.contains(BEGIN_MARKER) || line.contains(BELOW_MARKER) {
+            protecting = true;
+            found = true;
+        }
+        if protecting {
+            region.push(line);
+        }
+        if line.contains(END_MARKER) {
+            protecting = false;
+        }
+    }
+    if found {
+        Some(region.join("\n"))
+    } else {
+        None
+    }
+}
+
+// The fingerprint of a protected region, as `protected_fingerprint` in info.toml
+pub fn fingerprint(region: &str) -> String {
+    hex(&Sha256::digest(region.as_bytes()))
+}
+
+// Check the protected regions of the exercise against the fingerprint in
+// info.toml, or else against the original version of the exercise.
+// Exercises without either have nothing to protect.
+pub fn check_protected(exercise: &Exercise) -> Result<(), Tampering> {
+    if let Mode::Cargo = exercise.mode {
+        return Ok(());
+    }
+    let original = original_source(exercise).and_then(|source| protected_region(exercise, &source));
+    let expected = match exercise
+        .protected_fingerprint
+        .clone()
+        .or_else(|| original.as_deref().map(fingerprint))
+    {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+
+    let source = fs::read_to_string(&exercise.path).unwrap_or_default();
+    if is_intact(exercise, &source, &expected) {
+        return Ok(());
+    }
+    // Removed markers leave nothing to protect, which doesn't match either
+    let current = protected_region(exercise, &source).unwrap_or_default();
+    Err(Tampering { original, current })
+}
+
+// Whether the protected regions of the source have the expected fingerprint.
+// The lines of `protected_lines` are those of the original, which move when
+// the student adds or removes lines above them, so any run of as many lines
+// anywhere in the source will do.
+fn is_intact(exercise: &Exercise, source: &str, expected: &str) -> bool {
+    if let Some([first, last]) = exercise.protected_lines {
+        let count = (last + 1).saturating_sub(first.max(1)).max(1);
+        let lines: Vec<&str> = source.lines().map(str::trim_end).collect();
+        return lines.windows(count).any(|window| fingerprint(&window.join("\n")) == expected);
+    }
+    fingerprint(&protected_region(exercise, source).unwrap_or_default()) == expected
+}
+
+// Tell the end user that they changed a part of the exercise they
+// shouldn't have, and show what they changed
+pub fn warn_tampered(exercise: &Exercise) {
+    warn!("{} changes code it isn't allowed to change!", exercise);
+    let tampering = match check_protected(exercise) {
+        Ok(()) => return,
+        Err(tampering) => tampering,
+    };
+    match &tampering.original {
+        Some(original) => {
+            println!("Undo these changes to the protected part of the exercise (- original, + yours):");
+            print_diff(&format!("{}\n", original), &format!("{}\n", tampering.current));
+        }
+        None => println!("The protected part of the exercise no longer matches its original version."),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn exercise(protected_lines: Option<[usize; 2]>) -> Exercise {
+        Exercise {
+            name: "protected".into(),
+            path: PathBuf::from("protected.rs"),
+            mode: Mode::Test,
+            protected_lines,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_protected_region() {
+        let source = "fn a() {}\n// BEGIN PROTECTED\nfn b() {}   \n// END PROTECTED\nfn c() {}\n\
+                      // Don't change anything below this line.\nfn d() {}\n";
+        assert_eq!(
+            protected_region(&exercise(None), source).unwrap(),
+            "// BEGIN PROTECTED\nfn b() {}\n// END PROTECTED\n// Don't change anything below this line.\nfn d() {}"
+        );
+        assert_eq!(protected_region(&exercise(Some([3, 5])), source).unwrap(), "fn b() {}\n// END PROTECTED\nfn c() {}");
+        assert_eq!(protected_region(&exercise(None), "fn a() {}\n"), None);
+    }
+
+    #[test]
+    fn test_protected_lines_follow_the_code() {
+        let exercise = exercise(Some([2, 3]));
+        let expected = fingerprint("fn b() {}\nfn c() {}");
+        assert!(is_intact(&exercise, "fn a() {}\nfn b() {}\nfn c() {}\n", &expected));
+        assert!(is_intact(&exercise, "use std::fs;\n\nfn a() {}\nfn b() {}\nfn c() {}\n", &expected));
+        assert!(!is_intact(&exercise, "fn a() {}\nfn b() { 1 }\nfn c() {}\n", &expected));
+    }
+}
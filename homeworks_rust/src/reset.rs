@@ -0,0 +1,115 @@
+use crate::exercise::{normalize_path, Exercise};
+use crate::progress::now;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// The exercises as committed in git when this binary was built, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/original_exercises.rs"));
+
+const BACKUP_DIR_PATH: &str = ".rustlings-backups";
+
+// The version of the exercise that ships with the course.
+// Exercises built into the binary are taken from there, anything else from
+// a pristine `<path>.orig` copy next to the exercise.
+pub fn original_source(exercise: &Exercise) -> Option<String> {
+    let path = normalize_path(&exercise.path).to_string_lossy().replace('\\', "/");
+    ORIGINAL_EXERCISES
+        .iter()
+        .find(|(original_path, _)| *original_path == path)
+        .map(|(_, source)| source.to_string())
+        .or_else(|| {
+            let mut orig_path = exercise.path.clone().into_os_string();
+            orig_path.push(".orig");
+            fs::read_to_string(orig_path).ok()
+        })
+}
+
+// Restore the exercise to its original version once the student confirms,
+// keeping a backup of their current version
+pub fn reset(exercise: &Exercise) -> Result<(), ()> {
+    let original = match original_source(exercise) {
+        Some(original) => original,
+        None => {
+            println!("No original version of {} is available.", exercise);
+            return Err(());
+        }
+    };
+
+    let current = fs::read_to_string(&exercise.path).ok();
+    if current.as_deref() == Some(original.as_str()) {
+        println!("{} is already in its original state.", exercise);
+        return Ok(());
+    }
+
+    if !confirm(&format!(
+        "This will discard your changes to {}. Reset it?",
+        exercise.path.display()
+    )) {
+        println!("Left {} untouched.", exercise);
+        return Ok(());
+    }
+
+    if let Some(current) = current {
+        match backup(exercise, &current) {
+            Ok(backup_path) => println!("Saved your version to {}", backup_path.display()),
+            Err(e) => {
+                println!("Failed to back up {}: {}", exercise.path.display(), e);
+                return Err(());
+            }
+        }
+    }
+
+    if let Err(e) = fs::write(&exercise.path, original) {
+        println!("Failed to reset {}: {}", exercise.path.display(), e);
+        return Err(());
+    }
+    success!("Reset {} to its original state!", exercise);
+    Ok(())
+}
+
+// Ask a yes/no question on the terminal, defaulting to no
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    let _ignored = io::stdout().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+// Copy the student's version of the exercise outside of ./homeworks,
+// so the backup doesn't set off watch mode
+fn backup(exercise: &Exercise, current: &str) -> io::Result<PathBuf> {
+    let backup_path = Path::new(BACKUP_DIR_PATH).join(format!("{}.{}.rs", exercise.name, now()));
+    fs::create_dir_all(BACKUP_DIR_PATH)?;
+    fs::write(&backup_path, current)?;
+    Ok(backup_path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    fn exercise(path: &str) -> Exercise {
+        Exercise {
+            name: String::from("variables1"),
+            path: PathBuf::from(path),
+            homework: Some(5),
+            mode: Mode::Compile,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    #[cfg_attr(not(embedded_exercises), ignore = "built outside of a git checkout")]
+    fn test_original_source_is_embedded() {
+        let path = "homeworks/homework5/variables/variables1.rs";
+        let original = original_source(&exercise(path)).unwrap();
+        assert!(original.contains("I AM NOT DONE"));
+        assert_eq!(original_source(&exercise(&format!("./{}", path))), Some(original));
+        assert!(original_source(&exercise("homeworks/missing.rs")).is_none());
+    }
+}
@@ -1,61 +1,97 @@
+use crate::cache::Cache;
 use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
+use crate::format::{self, OutputFormat};
+use crate::progress::Progress;
+use crate::protected::warn_tampered;
+use crate::verify::{evaluate, warn_compile_fail, warn_stopped, warn_wrong_output, Outcome, Report, RunMode};
 use indicatif::ProgressBar;
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
+// The errors of the compilation, if any, are saved to the progress record
+pub fn run(
+    exercise: &Exercise,
+    verbose: bool,
+    output_format: OutputFormat,
+    cache: Option<&Cache>,
+    progress: &mut Progress,
+) -> Result<(), ()> {
+    let report = match output_format {
+        OutputFormat::Human => compile_and_run(exercise, verbose, cache),
+        OutputFormat::Json => {
+            let report = evaluate(exercise, RunMode::NonInteractive, cache);
+            format::print_json_report(&report);
+            format::print_json_summary(std::slice::from_ref(&report));
+            report
+        }
+    };
+
+    progress.record_compile_errors(exercise, report.errors());
+    progress.save();
+
+    if report.outcome.is_success() {
+        Ok(())
+    } else {
+        Err(())
     }
-    Ok(())
 }
 
 // Invoke the rust compiler on the path of the given exercise
-// and run the ensuing binary.
-// This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
+// and run the ensuing binary or test harness.
+// Output of non-test binaries is always displayed
+fn compile_and_run<'a>(exercise: &'a Exercise, verbose: bool, cache: Option<&Cache>) -> Report<'a> {
     let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {}...", exercise).as_str());
+    progress_bar.set_message(format!("Running {}...", exercise).as_str());
     progress_bar.enable_steady_tick(100);
 
+    let report = evaluate(exercise, RunMode::NonInteractive, cache);
+    progress_bar.finish_and_clear();
 
-    println!("Compiling: {:?}", exercise.name);
-
-    let compilation_result = exercise.compile();
-    let compilation = match compilation_result {
-        Ok(compilation) => compilation,
-        Err(output) => {
-            progress_bar.finish_and_clear();
+    match report.outcome {
+        Outcome::CompileFailed => {
             warn!(
                 "Compilation of {} failed!, Compiler error message:\n",
                 exercise
             );
-            println!("{}", output.stderr);
-            return Err(());
+            println!("{}", report.stderr());
         }
-    };
-
-    progress_bar.set_message(format!("Running {}...", exercise).as_str());
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
-
-    match result {
-        Ok(output) => {
-            println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
-            Ok(())
+        Outcome::TestsFailed => {
+            warn!(
+                "Testing of {} failed! Please try again. Here's the output:",
+                exercise
+            );
+            println!("{}", report.stdout());
         }
-        Err(output) => {
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
+        Outcome::RunFailed => {
+            println!("{}", report.stdout());
+            println!("{}", report.stderr());
 
             warn!("Ran {} with errors", exercise);
-            Err(())
         }
+        Outcome::TimedOut | Outcome::ExceededMemory | Outcome::ExceededOutput => {
+            warn_stopped(&report);
+        }
+        Outcome::WrongOutput => warn_wrong_output(&report),
+        Outcome::CompiledUnexpectedly | Outcome::UnexpectedErrors => warn_compile_fail(&report),
+        Outcome::Tampered => warn_tampered(exercise),
+        Outcome::Compiled | Outcome::FailedAsExpected | Outcome::PendingMarker => match exercise.mode {
+            Mode::Test | Mode::Cargo => {
+                if verbose {
+                    println!("{}", report.stdout());
+                }
+                success!("Successfully tested {}", exercise);
+            }
+            Mode::CompileFail => {
+                println!("{}", report.stderr());
+                success!("{} fails to compile for the right reason", exercise);
+            }
+            _ => {
+                println!("{}", report.stdout());
+                success!("Successfully ran {}", exercise);
+            }
+        },
     }
+    report
 }
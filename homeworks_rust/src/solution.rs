@@ -0,0 +1,48 @@
+use crate::diff::print_diff;
+use crate::exercise::Exercise;
+use crate::progress::Progress;
+use std::fs;
+
+// How many failed verifications unlock a reference solution by default
+const DEFAULT_SOLUTION_UNLOCK_ATTEMPTS: u32 = 3;
+
+// Show the difference between the student's version of the exercise and its
+// reference solution, once the student has earned it.
+// Every unlock is saved to the progress record for the instructors.
+pub fn solution(exercise: &Exercise, progress: &mut Progress) -> Result<(), ()> {
+    let solution_path = match &exercise.solution {
+        Some(solution_path) => solution_path,
+        None => {
+            println!("There is no reference solution for {}.", exercise);
+            return Err(());
+        }
+    };
+
+    let unlock_attempts = exercise
+        .solution_unlock_attempts
+        .unwrap_or(DEFAULT_SOLUTION_UNLOCK_ATTEMPTS);
+    let failures = progress.failures(exercise);
+    if failures < unlock_attempts && !progress.is_done(exercise) {
+        println!(
+            "The solution of {} unlocks after {} failed attempts ({} so far), or once you are done with it.",
+            exercise, unlock_attempts, failures
+        );
+        return Err(());
+    }
+
+    let solution = match fs::read_to_string(solution_path) {
+        Ok(solution) => solution,
+        Err(e) => {
+            println!("Failed to read the solution {}: {}", solution_path.display(), e);
+            return Err(());
+        }
+    };
+    let current = fs::read_to_string(&exercise.path).unwrap_or_default();
+
+    progress.record_solution_unlock(exercise);
+    progress.save();
+
+    println!("Differences between {} and its reference solution:", exercise);
+    print_diff(&current, &solution);
+    Ok(())
+}
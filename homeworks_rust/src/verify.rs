@@ -1,146 +1,483 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::cache::{Cache, CacheEntry};
+use crate::diagnostics::CompileError;
+use crate::diff::print_diff;
+use crate::exercise::{Exercise, ExerciseOutput, ExpectedOutput, Mode, State};
+use crate::format::{self, OutputFormat};
+use crate::junit;
+use crate::limits::Stopped;
+use crate::progress::Progress;
+use crate::protected::{check_protected, warn_tampered};
 use console::style;
 use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The settings shared by every verification of a run of rustlings
+pub struct VerifyOptions {
+    // Whether or not the test harness outputs are displayed
+    pub verbose: bool,
+    // How the results are printed
+    pub output_format: OutputFormat,
+    // Where to write a JUnit XML report of the results, if anywhere
+    pub junit: Option<PathBuf>,
+    // How many exercises are compiled and run at the same time
+    pub jobs: usize,
+    // Where builds are cached, unless caching is disabled
+    pub cache: Option<Cache>,
+    // Whether failures count towards unlocking reference solutions. Watch
+    // mode verifies on every save, half-typed code included, so its don't.
+    pub count_failures: bool,
+}
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
-// If the Exercise being verified is a test, the verbose boolean
-// determines whether or not the test harness outputs are displayed.
+// With more than one job, the exercises after the one being reported are
+// evaluated ahead on worker threads, but results are still reported in order.
+// The outcome of every verified exercise is saved to the progress record.
 pub fn verify<'a>(
     start_at: impl IntoIterator<Item = &'a Exercise>,
-    verbose: bool,
-) -> Result<(), &'a Exercise> {
-    
-    // loops over iterator of exercises
-    for exercise in start_at {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose),
-            Mode::Compile => compile_and_run_interactively(exercise),
-            Mode::Clippy => compile_only(exercise),
-        };
-        if !compile_result.unwrap_or(false) {
-            return Err(exercise);
+    progress: &mut Progress,
+    options: &VerifyOptions,
+) -> Result<(), Failure<'a>> {
+    let exercises: Vec<&'a Exercise> = start_at.into_iter().collect();
+    let next_index = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = channel();
+    let mut evaluated_ahead: HashMap<usize, Report<'a>> = HashMap::new();
+    let mut reports = Vec::new();
+    let mut result = Ok(());
+
+    thread::scope(|scope| {
+        if options.jobs > 1 {
+            for _ in 0..options.jobs {
+                let (exercises, next_index, stop, sender) = (&exercises, &next_index, &stop, sender.clone());
+                scope.spawn(move || loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    if index >= exercises.len() || stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let report = evaluate(exercises[index], RunMode::Interactive, options.cache.as_ref());
+                    if sender.send((index, report)).is_err() {
+                        break;
+                    }
+                });
+            }
         }
+
+        // loops over the exercises in order
+        for (index, &exercise) in exercises.iter().enumerate() {
+            let progress_bar = match options.output_format {
+                OutputFormat::Human => ProgressBar::new_spinner(),
+                OutputFormat::Json => ProgressBar::hidden(),
+            };
+            progress_bar.set_message(format!("Verifying {}...", exercise).as_str());
+            progress_bar.enable_steady_tick(100);
+
+            let report = if options.jobs > 1 {
+                loop {
+                    if let Some(report) = evaluated_ahead.remove(&index) {
+                        break report;
+                    }
+                    let (evaluated_index, report) = receiver.recv().expect("A verification worker died!");
+                    evaluated_ahead.insert(evaluated_index, report);
+                }
+            } else {
+                evaluate(exercise, RunMode::Interactive, options.cache.as_ref())
+            };
+            progress_bar.finish_and_clear();
+
+            match options.output_format {
+                OutputFormat::Human => print_report(&report, options.verbose),
+                OutputFormat::Json => format::print_json_report(&report),
+            }
+            progress.record(exercise, report.outcome.is_success());
+            if options.count_failures && !report.outcome.is_success() {
+                progress.record_failure(exercise);
+            }
+            // Tampered exercises aren't compiled at all
+            if report.outcome != Outcome::Tampered {
+                progress.record_compile_errors(exercise, report.errors());
+            }
+            progress.save();
+
+            if !report.outcome.passed() {
+                result = Err(Failure {
+                    exercise,
+                    errors: report.errors().to_vec(),
+                });
+                reports.push(report);
+                break;
+            }
+            reports.push(report);
+        }
+        stop.store(true, Ordering::SeqCst);
+    });
+
+    if options.output_format == OutputFormat::Json {
+        format::print_json_summary(&reports);
+    }
+    if let Some(path) = &options.junit {
+        // Exercises after the first failure are never reached
+        let skipped = &exercises[reports.len()..];
+        junit::write_report(path, &reports, skipped);
     }
-    Ok(())
+    result
 }
 
-enum RunMode {
+// The exercise a verification stopped at
+pub struct Failure<'a> {
+    pub exercise: &'a Exercise,
+    // The compiler errors it hit, if it didn't compile
+    pub errors: Vec<CompileError>,
+}
+
+#[derive(Clone, Copy)]
+pub enum RunMode {
+    // The exercise is being verified, so a pending "I AM NOT DONE"
+    // marker counts against it and Clippy exercises are only linted
     Interactive,
+    // The exercise is being run on its own
     NonInteractive,
 }
 
-// Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose)?;
-    Ok(())
+// The outcome of compiling and running a single exercise
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    // The exercise compiled and its binary or tests ran successfully
+    Compiled,
+    // The exercise did not compile
+    CompileFailed,
+    // The test harness of the exercise reported failing tests
+    TestsFailed,
+    // The binary of the exercise exited with an error
+    RunFailed,
+    // The binary of the exercise ran longer than its timeout
+    TimedOut,
+    // The binary of the exercise tried to use more memory than its limit
+    ExceededMemory,
+    // The binary of the exercise printed more than its output limit
+    ExceededOutput,
+    // The binary of the exercise ran successfully, but printed the wrong output
+    WrongOutput,
+    // The compile-fail exercise failed to compile with exactly the expected errors
+    FailedAsExpected,
+    // The compile-fail exercise compiled
+    CompiledUnexpectedly,
+    // The compile-fail exercise failed to compile with other errors than expected
+    UnexpectedErrors,
+    // The exercise passed, but it still has its "I AM NOT DONE" marker
+    PendingMarker,
+    // The student changed a protected region of the exercise
+    Tampered,
 }
 
-// Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {}...", exercise).as_str());
-    progress_bar.enable_steady_tick(100);
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let outcome = match self {
+            Outcome::Compiled => "compiled",
+            Outcome::CompileFailed => "compile-failed",
+            Outcome::TestsFailed => "tests-failed",
+            Outcome::RunFailed => "run-failed",
+            Outcome::TimedOut => "timed-out",
+            Outcome::ExceededMemory => "exceeded-memory",
+            Outcome::ExceededOutput => "exceeded-output",
+            Outcome::WrongOutput => "wrong-output",
+            Outcome::FailedAsExpected => "failed-as-expected",
+            Outcome::CompiledUnexpectedly => "compiled-unexpectedly",
+            Outcome::UnexpectedErrors => "unexpected-errors",
+            Outcome::PendingMarker => "pending-marker",
+            Outcome::Tampered => "tampered",
+        };
+        write!(f, "{}", outcome)
+    }
+}
 
-    let _ = compile(exercise, &progress_bar)?;
-    progress_bar.finish_and_clear();
+impl Outcome {
+    // Whether the exercise passed, marker included
+    pub fn passed(self) -> bool {
+        matches!(self, Outcome::Compiled | Outcome::FailedAsExpected)
+    }
 
-    success!("Successfully compiled {}!", exercise);
-    Ok(prompt_for_completion(exercise, None))
+    // Whether the code of the exercise itself is correct
+    pub fn is_success(self) -> bool {
+        self.passed() || self == Outcome::PendingMarker
+    }
 }
 
-// Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {}...", exercise).as_str());
-    progress_bar.enable_steady_tick(100);
+// Everything that was observed while compiling and running an exercise
+pub struct Report<'a> {
+    pub exercise: &'a Exercise,
+    pub outcome: Outcome,
+    // The time spent in `Exercise::compile()`
+    pub compile_duration: Duration,
+    // The time spent in `CompiledExercise::run()`
+    pub run_duration: Duration,
+    // The compiler output on failed compilations, the binary's output otherwise
+    pub output: Option<ExerciseOutput>,
+}
 
-    let compilation = compile(exercise, &progress_bar)?;
+impl<'a> Report<'a> {
+    pub fn duration(&self) -> Duration {
+        self.compile_duration + self.run_duration
+    }
 
-    // println!("exrcise list: {:?}",)
+    pub fn stdout(&self) -> &str {
+        self.output.as_ref().map_or("", |output| &output.stdout)
+    }
 
-    println!("Compiling: {:?}", exercise.name);
+    pub fn stderr(&self) -> &str {
+        self.output.as_ref().map_or("", |output| &output.stderr)
+    }
 
-    progress_bar.set_message(format!("Running {}...", exercise).as_str());
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
+    pub fn errors(&self) -> &[CompileError] {
+        self.output.as_ref().map_or(&[], |output| &output.errors)
+    }
+}
 
-    let output = match result {
-        Ok(output) => output,
-        Err(output) => {
-            warn!("Ran {} with errors", exercise);
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-            return Err(());
-        }
+// Compile and run the given Exercise without printing anything,
+// and report what happened.
+// When verifying, a cached result of the same source is reused as is,
+// and exercises whose protected regions changed aren't compiled at all.
+pub fn evaluate<'a>(exercise: &'a Exercise, run_mode: RunMode, cache: Option<&Cache>) -> Report<'a> {
+    if let (RunMode::Interactive, Err(_)) = (run_mode, check_protected(exercise)) {
+        return Report {
+            exercise,
+            outcome: Outcome::Tampered,
+            compile_duration: Duration::default(),
+            run_duration: Duration::default(),
+            output: None,
+        };
+    }
+
+    let cache_entry = cache.and_then(|cache| cache.entry(exercise));
+    let cached_result = match run_mode {
+        RunMode::Interactive => cache_entry.as_ref().and_then(CacheEntry::result),
+        RunMode::NonInteractive => None,
     };
 
-    success!("Successfully ran {}!", exercise);
+    let mut report = match cached_result {
+        Some(result) => Report {
+            exercise,
+            outcome: result.outcome,
+            compile_duration: Duration::default(),
+            run_duration: Duration::default(),
+            output: Some(ExerciseOutput {
+                stdout: result.stdout,
+                stderr: result.stderr,
+                stopped: None,
+                errors: result.errors,
+            }),
+        },
+        None => compile_and_run(exercise, run_mode, cache_entry.as_ref()),
+    };
+
+    if let (Mode::Compile, Outcome::Compiled) = (exercise.mode, report.outcome) {
+        if let Some(expected) = exercise.expected_output() {
+            if !expected.matches(report.stdout()) {
+                report.outcome = Outcome::WrongOutput;
+            }
+        }
+    }
+
+    if let Mode::CompileFail = exercise.mode {
+        report.outcome = match report.outcome {
+            Outcome::CompileFailed if exercise.fails_as_expected(report.errors()) => Outcome::FailedAsExpected,
+            Outcome::CompileFailed => Outcome::UnexpectedErrors,
+            _ => Outcome::CompiledUnexpectedly,
+        };
+    }
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout)))
+    if let RunMode::Interactive = run_mode {
+        if report.outcome.passed() && !exercise.looks_done() {
+            report.outcome = Outcome::PendingMarker;
+        }
+    }
+    report
 }
 
-// Compile the given Exercise as a test harness and display
-// the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {}...", exercise).as_str());
-    progress_bar.enable_steady_tick(100);
+// Compile the given Exercise, or reuse its cached binary, and run it
+fn compile_and_run<'a>(exercise: &'a Exercise, run_mode: RunMode, cache_entry: Option<&CacheEntry>) -> Report<'a> {
+    let mut report = Report {
+        exercise,
+        outcome: Outcome::Compiled,
+        compile_duration: Duration::default(),
+        run_duration: Duration::default(),
+        output: None,
+    };
 
-    let compilation = compile(exercise, &progress_bar)?;
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
+    let start = Instant::now();
+    let cached_compilation = cache_entry
+        .and_then(CacheEntry::binary)
+        .and_then(|binary| exercise.load_compiled(&binary));
+    let compilation_result = match cached_compilation {
+        Some(compilation) => Ok(compilation),
+        None => exercise.compile(),
+    };
+    report.compile_duration = start.elapsed();
 
-    match result {
-        Ok(output) => {
-            if verbose {
-                println!("{}", output.stdout);
+    let compilation = match compilation_result {
+        Ok(compilation) => compilation,
+        Err(output) => {
+            report.outcome = Outcome::CompileFailed;
+            if let Some(cache_entry) = cache_entry {
+                cache_entry.store_result(report.outcome, &output);
             }
-            success!("Successfully tested {}", &exercise);
-            if let RunMode::Interactive = run_mode {
-                Ok(prompt_for_completion(exercise, None))
-            } else {
-                Ok(true)
+            report.output = Some(output);
+            return report;
+        }
+    };
+    if let Some(cache_entry) = cache_entry {
+        cache_entry.store_binary(&compilation);
+    }
+
+    // Clippy exercises are done once they pass the lints,
+    // and compile-fail exercises that compile are never run
+    let skip_run = matches!(
+        (exercise.mode, run_mode),
+        (Mode::Clippy, RunMode::Interactive) | (Mode::CompileFail, _)
+    );
+    if !skip_run {
+        let start = Instant::now();
+        let result = compilation.run();
+        report.run_duration = start.elapsed();
+
+        let output = match result {
+            Ok(output) => output,
+            Err(output) => {
+                report.outcome = match (output.stopped, exercise.mode) {
+                    (Some(Stopped::TimedOut), _) => Outcome::TimedOut,
+                    (Some(Stopped::ExceededMemory), _) => Outcome::ExceededMemory,
+                    (Some(Stopped::ExceededOutput), _) => Outcome::ExceededOutput,
+                    (None, Mode::Test | Mode::Cargo) => Outcome::TestsFailed,
+                    (None, _) => Outcome::RunFailed,
+                };
+                output
             }
+        };
+        // Whether a run gets stopped depends on the limits, not just the source
+        if let (Some(cache_entry), None) = (cache_entry, output.stopped) {
+            cache_entry.store_result(report.outcome, &output);
         }
-        Err(output) => {
+        report.output = Some(output);
+    }
+    report
+}
+
+// Report the result of verifying an Exercise to the end user,
+// prompting them to move on if it passed.
+// If the Exercise is a test, the verbose boolean determines
+// whether or not the test harness outputs are displayed.
+fn print_report(report: &Report, verbose: bool) {
+    let exercise = report.exercise;
+    match report.outcome {
+        Outcome::CompileFailed => {
+            warn!(
+                "Compiling of {} failed! Please try again. Here's the output:",
+                exercise
+            );
+            println!("{}", report.stderr());
+        }
+        Outcome::TestsFailed => {
             warn!(
                 "Testing of {} failed! Please try again. Here's the output:",
                 exercise
             );
-            println!("{}", output.stdout);
-            Err(())
+            println!("{}", report.stdout());
         }
+        Outcome::RunFailed => {
+            warn!("Ran {} with errors", exercise);
+            println!("{}", report.stdout());
+            println!("{}", report.stderr());
+        }
+        Outcome::TimedOut | Outcome::ExceededMemory | Outcome::ExceededOutput => {
+            warn_stopped(report);
+        }
+        Outcome::WrongOutput => warn_wrong_output(report),
+        Outcome::CompiledUnexpectedly | Outcome::UnexpectedErrors => warn_compile_fail(report),
+        Outcome::Tampered => warn_tampered(exercise),
+        Outcome::Compiled | Outcome::FailedAsExpected | Outcome::PendingMarker => match exercise.mode {
+            Mode::Compile => {
+                success!("Successfully ran {}!", exercise);
+                prompt_for_completion(exercise, Some(report.stdout()));
+            }
+            Mode::Test | Mode::Cargo => {
+                if verbose {
+                    println!("{}", report.stdout());
+                }
+                success!("Successfully tested {}", exercise);
+                prompt_for_completion(exercise, None);
+            }
+            Mode::Clippy => {
+                success!("Successfully compiled {}!", exercise);
+                prompt_for_completion(exercise, None);
+            }
+            Mode::CompileFail => {
+                success!("{} fails to compile for the right reason!", exercise);
+                prompt_for_completion(exercise, Some(report.stderr()));
+            }
+        },
     }
 }
 
-// Compile the given Exercise and return an object with information
-// about the state of the compilation
-fn compile<'a, 'b>(
-    exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
-    let compilation_result = exercise.compile();
+// Show the end user how the output of their exercise differs from the expected one
+pub fn warn_wrong_output(report: &Report) {
+    let exercise = report.exercise;
+    warn!("{} ran, but it printed the wrong output!", exercise);
+    match exercise.expected_output() {
+        Some(ExpectedOutput::Exact(expected)) => {
+            println!("Differences between the expected output (-) and yours (+):");
+            print_diff(&format!("{}\n", expected.trim_end_matches('\n')), report.stdout());
+        }
+        Some(ExpectedOutput::Regex(regex)) => {
+            println!("Your output should match the regex {}, but it was:", regex);
+            println!("{}", report.stdout());
+        }
+        None => println!("{}", report.stdout()),
+    }
+}
 
-    match compilation_result {
-        Ok(compilation) => Ok(compilation),
-        Err(output) => {
-            progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stderr);
-            Err(())
+// Tell the end user why their compile-fail exercise didn't fail as expected
+pub fn warn_compile_fail(report: &Report) {
+    let exercise = report.exercise;
+    let expected = exercise.expected_errors.join(", ");
+    match report.outcome {
+        Outcome::CompiledUnexpectedly => {
+            warn!("{} compiled, but it should fail to compile!", exercise);
+            println!("Expected errors: {}", expected);
+        }
+        _ => {
+            warn!("{} failed to compile for the wrong reason!", exercise);
+            println!("Expected errors: {}", expected);
+            println!("{}", report.stderr());
         }
     }
 }
 
-fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) -> bool {
+// Tell the end user why their exercise was stopped while it was running,
+// and show what it printed until then
+pub fn warn_stopped(report: &Report) {
+    let exercise = report.exercise;
+    match report.outcome {
+        Outcome::TimedOut => warn!("{} took too long to run and was stopped!", exercise),
+        Outcome::ExceededMemory => warn!("{} tried to use too much memory and was stopped!", exercise),
+        _ => warn!("{} printed too much output and was stopped!", exercise),
+    }
+    println!("{}", report.stdout());
+    println!("{}", report.stderr());
+}
+
+fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<&str>) {
     let context = match exercise.state() {
-        State::Done => return true,
+        State::Done => return,
         State::Pending(context) => context,
     };
 
@@ -154,8 +491,9 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
 
     let success_msg = match exercise.mode {
         Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
+        Mode::Test | Mode::Cargo => "The code is compiling, and the tests pass!",
         Mode::Clippy => clippy_success_msg,
+        Mode::CompileFail => "The code fails to compile for the right reason!",
     };
 
     println!();
@@ -194,8 +532,6 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
             formatted_line
         );
     }
-
-    false
 }
 
 fn separator() -> console::StyledObject<&'static str> {
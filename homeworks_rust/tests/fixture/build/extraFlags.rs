@@ -0,0 +1,7 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn built_with_course_cfg() {
+        assert!(cfg!(course));
+    }
+}
@@ -0,0 +1,3 @@
+pub fn welcome() -> usize {
+    greeting::greet("Ferris")
+}
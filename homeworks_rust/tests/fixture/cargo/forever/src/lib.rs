@@ -0,0 +1,16 @@
+pub fn countdown() -> u32 {
+    let mut n = 0;
+    loop {
+        n = std::hint::black_box(n + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_down() {
+        assert_eq!(countdown(), 0);
+    }
+}
@@ -0,0 +1,13 @@
+pub fn welcome() -> String {
+    greeting::greet("Ferris")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn welcomes_ferris() {
+        assert_eq!(welcome(), "Hello, Ferris!");
+    }
+}
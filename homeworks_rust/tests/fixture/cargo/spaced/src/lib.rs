@@ -0,0 +1,7 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn gets_the_flag_in_one_piece() {
+        assert!(cfg!(course = "a b"));
+    }
+}
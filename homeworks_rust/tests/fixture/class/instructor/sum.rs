@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn sums_negative_numbers() {
+    assert_eq!(sum(-1, -2), -3);
+}
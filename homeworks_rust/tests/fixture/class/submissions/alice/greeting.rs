@@ -0,0 +1,4 @@
+fn main() {
+    let name = "Alice";
+    println!("Hello {}!", name);
+}
@@ -0,0 +1,13 @@
+pub fn sum(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum(1, 2), 3);
+    }
+}
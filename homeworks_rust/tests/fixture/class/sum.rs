@@ -0,0 +1,15 @@
+// I AM NOT DONE
+
+pub fn sum(a: i32, b: i32) -> i32 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum(1, 2), 3);
+    }
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let a: f64 = 0.1 + 0.2;
+    let b: f64 = std::env::args().count() as f64 * 0.3;
+    if a == b {
+        println!("equal");
+    }
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let greeting = String::from("hello");
+    let copied = greeting.clone();
+    println!("{} {}", greeting, copied);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let greeting: String = 5;
+    println!("{}", greeting);
+}
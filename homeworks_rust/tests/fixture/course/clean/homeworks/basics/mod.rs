@@ -0,0 +1 @@
+mod greeting;
@@ -0,0 +1,3 @@
+mod greeting;
+mod farewell;
+mod silent;
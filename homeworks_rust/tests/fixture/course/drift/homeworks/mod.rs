@@ -0,0 +1,2 @@
+mod basics;
+mod ghost;
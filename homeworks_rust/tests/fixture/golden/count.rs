@@ -0,0 +1,5 @@
+fn main() {
+    for i in 1..4 {
+        println!("{}", i);
+    }
+}
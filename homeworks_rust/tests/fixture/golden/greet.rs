@@ -0,0 +1,7 @@
+use std::io;
+
+fn main() {
+    let mut name = String::new();
+    io::stdin().read_line(&mut name).unwrap();
+    println!("Hello, {}!", name.trim());
+}
@@ -0,0 +1,8 @@
+use std::io;
+
+fn main() {
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    let ms: u32 = input.trim().parse().unwrap();
+    print!("Took {} ms", ms);
+}
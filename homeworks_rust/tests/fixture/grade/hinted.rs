@@ -0,0 +1,7 @@
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn passes() {
+        assert_eq!(2 + 2, 4);
+    }
+}
@@ -0,0 +1,22 @@
+// The student made their own test pass by special-casing it
+fn double(n: i32) -> i32 {
+    if n == 2 {
+        4
+    } else {
+        n
+    }
+}
+
+fn main() {
+    println!("{}", double(21));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles_two() {
+        assert_eq!(double(2), 4);
+    }
+}
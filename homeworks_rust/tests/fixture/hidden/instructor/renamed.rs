@@ -0,0 +1,7 @@
+use super::*;
+
+#[test]
+fn doubles_negative_numbers() {
+    let secret_expected_value = -6;
+    assert_eq!(double(-3), secret_expected_value);
+}
@@ -0,0 +1,8 @@
+// The student renamed the function the hidden tests call
+fn twice(n: i32) -> i32 {
+    n * 2
+}
+
+fn main() {
+    println!("{}", twice(21));
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let huge: Vec<u8> = Vec::with_capacity(1 << 30);
+    println!("{}", huge.capacity());
+}
@@ -0,0 +1,8 @@
+// lineRange.rs
+fn answer() -> i32 {
+    42
+}
+
+fn main() {
+    assert_eq!(answer(), 42);
+}
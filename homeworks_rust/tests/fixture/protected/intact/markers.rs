@@ -0,0 +1,12 @@
+// markers.rs
+// Make `total` add up the numbers.
+
+fn total(numbers: &[i32]) -> i32 {
+    numbers.iter().sum()
+}
+
+// BEGIN PROTECTED
+fn main() {
+    assert_eq!(total(&[1, 2, 3]), 6);
+}
+// END PROTECTED
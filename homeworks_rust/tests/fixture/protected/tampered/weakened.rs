@@ -0,0 +1,18 @@
+// weakened.rs
+// Make `is_even` tell even numbers from odd ones.
+
+fn is_even(n: i32) -> bool {
+    n % 2 == 1
+}
+
+// Don't change anything below this line.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_is_even() {
+        assert!(!is_even(4));
+    }
+}
@@ -0,0 +1,5 @@
+// fake_exercise
+
+fn main() {
+    println!("solved");
+}
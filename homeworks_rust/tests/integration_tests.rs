@@ -3,8 +3,43 @@ use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
 use std::fs::File;
 use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+// A copy of the fixture in a temporary directory, for tests that change it
+fn copy_fixture(name: &str) -> PathBuf {
+    fn copy_dir(from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap().flatten() {
+            if entry.path().is_dir() {
+                copy_dir(&entry.path(), &to.join(entry.file_name()));
+            } else {
+                std::fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+            }
+        }
+    }
+    let dir = std::env::temp_dir().join(format!("rustlings_fixture_{}_{}", name, std::process::id()));
+    let _ignored = std::fs::remove_dir_all(&dir);
+    copy_dir(&Path::new("tests/fixture").join(name), &dir);
+    dir
+}
+
+// A copy of the state fixture whose finished exercise was verified as it is now,
+// and whose other exercises were never verified
+fn verified_state_fixture() -> PathBuf {
+    use sha2::{Digest, Sha256};
+
+    let dir = copy_fixture("state");
+    let source = std::fs::read(dir.join("finished_exercise.rs")).unwrap();
+    let source_hash: String = Sha256::digest(&source).iter().map(|byte| format!("{:02x}", byte)).collect();
+    let progress = format!(
+        "[exercises.finished_exercise]\nverified = true\nsource_hash = \"{}\"\nverified_at = 0\nattempts = 1\n",
+        source_hash
+    );
+    std::fs::write(dir.join(".rustlings-progress.toml"), progress).unwrap();
+    dir
+}
+
 #[test]
 fn runs_without_arguments() {
     let mut cmd = Command::cargo_bin("rustlings").unwrap();
@@ -44,7 +79,7 @@ fn verify_fails_if_some_fails() {
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
+        .args(["run", "compSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -54,7 +89,7 @@ fn run_single_compile_success() {
 fn run_single_compile_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
+        .args(["run", "compFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -64,7 +99,7 @@ fn run_single_compile_failure() {
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -74,7 +109,7 @@ fn run_single_test_success() {
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
+        .args(["run", "testFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -84,7 +119,7 @@ fn run_single_test_failure() {
 fn run_single_test_not_passed() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
+        .args(["run", "testNotPassed.rs"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -104,7 +139,7 @@ fn run_single_test_no_filename() {
 fn run_single_test_no_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
+        .args(["run", "compNoExercise.rs"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(1);
@@ -114,7 +149,7 @@ fn run_single_test_no_exercise() {
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
+        .args(["hint", "testFailure"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(0)
@@ -123,7 +158,7 @@ fn get_hint_for_single_test() {
 
 #[test]
 fn all_exercises_require_confirmation() {
-    for exercise in glob("exercises/**/*.rs").unwrap() {
+    for exercise in glob("homeworks/**/*.rs").unwrap() {
         let path = exercise.unwrap();
         if path.file_name().unwrap() == "mod.rs" {
             continue
@@ -150,7 +185,7 @@ fn all_exercises_require_confirmation() {
 fn run_compile_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
+        .args(["run", "pending_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -161,7 +196,7 @@ fn run_compile_exercise_does_not_prompt() {
 fn run_test_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
+        .args(["run", "pending_test_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -172,7 +207,7 @@ fn run_test_exercise_does_not_prompt() {
 fn run_single_test_success_with_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
+        .args(["--nocapture", "run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -183,7 +218,7 @@ fn run_single_test_success_with_output() {
 fn run_single_test_success_without_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -194,7 +229,7 @@ fn run_single_test_success_without_output() {
 fn run_rustlings_list() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success();
@@ -202,44 +237,983 @@ fn run_rustlings_list() {
 
 #[test]
 fn run_rustlings_list_no_pending() {
+    let dir = copy_fixture("success");
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
-        .current_dir("tests/fixture/success")
+        .args(["verify"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Pending").not());
+    std::fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list_both_done_and_pending() {
+    let dir = verified_state_fixture();
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
-        .current_dir("tests/fixture/state")
+        .args(["list"])
+        .current_dir(&dir)
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").and(predicates::str::contains("Pending")));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_list_unverified_is_pending() {
+    let dir = copy_fixture("state");
+    let _ignored = std::fs::remove_file(dir.join(".rustlings-progress.toml"));
+    // finished_exercise has no marker, but was never verified
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--filter", "finished"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Pending").and(predicates::str::contains("0 / 1")));
+    std::fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list_without_pending() {
+    let dir = verified_state_fixture();
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
-        .current_dir("tests/fixture/state")
+        .args(["list", "--solved"])
+        .current_dir(&dir)
         .assert()
         .success()
-        .stdout(predicates::str::contains("Pending").not());
+        .stdout(predicates::str::contains("Pending").not().and(predicates::str::contains("finished_exercise")));
+    std::fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
 fn run_rustlings_list_without_done() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
+        .args(["list", "--unsolved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn run_rustlings_list_with_filter() {
+    let dir = verified_state_fixture();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--filter", "finished"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("finished_exercise")
+                .and(predicates::str::contains("pending_exercise").not())
+                .and(predicates::str::contains("1 / 1")),
+        );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verify_json_reports_failure() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--format", "json"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains(r#""outcome":"compile-failed""#)
+                .and(predicates::str::contains(r#""type":"summary""#)),
+        );
+}
+
+#[test]
+fn run_single_test_success_json() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testSuccess", "--format", "json"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains(r#""outcome":"compiled""#)
+                .and(predicates::str::contains("THIS TEST TOO SHALL PASS")),
+        );
+}
+
+#[test]
+fn verify_writes_junit_report() {
+    let report_path = std::env::temp_dir().join(format!("rustlings-junit-{}.xml", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .arg("--junit")
+        .arg(&report_path)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1);
+
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    std::fs::remove_file(&report_path).unwrap();
+    assert!(report.contains(r#"<testcase name="compFailure""#));
+    assert!(report.contains(r#"<failure message="compile-failed""#));
+    assert!(report.contains(r#"<testcase name="testFailure""#));
+    assert!(report.contains("<skipped/>"));
+}
+
+#[test]
+fn run_rustlings_homework_list() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["homework", "list"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"(?m)^1\s+3\s").unwrap());
+}
+
+#[test]
+fn run_rustlings_homework_unknown() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["homework", "9"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercises found for homework '9'"));
+}
+
+#[test]
+fn run_rustlings_homework_json_is_only_json() {
+    let output = Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["homework", "1", "--format", "json"])
+        .current_dir("tests/fixture/watch")
+        .with_stdin()
+        .buffer("quit\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(r#""outcome":"compile-failed""#));
+    for line in stdout.lines() {
+        assert!(serde_json::from_str::<serde_json::Value>(line).is_ok(), "not JSON: {:?}", line);
+    }
+}
+
+#[test]
+fn run_rustlings_homework_failures_dont_unlock_solutions() {
+    let dir = copy_fixture("watch");
+    let _ignored = std::fs::remove_file(dir.join(".rustlings-progress.toml"));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["homework", "1"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("quit\n")
+        .assert()
+        .success();
+    let progress = std::fs::read_to_string(dir.join(".rustlings-progress.toml")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(progress.contains("attempts = 1"));
+    assert!(progress.contains("failures = 0"));
+}
+
+#[test]
+fn run_rustlings_homework_saves_revealed_hints_at_once() {
+    use std::io::Write;
+
+    let dir = copy_fixture("watch");
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("rustlings"))
+        .args(["homework", "1"])
+        .current_dir(&dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"hint\n").unwrap();
+
+    // Killed like a closed terminal would, without quitting from the shell
+    let progress_path = dir.join(".rustlings-progress.toml");
+    let mut saved = false;
+    for _ in 0..100 {
+        let progress = std::fs::read_to_string(&progress_path).unwrap_or_default();
+        if progress.contains("hints_used = 1") {
+            saved = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(saved);
+}
+
+#[test]
+fn run_rustlings_list_by_homework() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--homework", "2"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("0 / 0"));
+}
+
+#[test]
+fn verify_all_success_in_parallel() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--jobs", "4"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn verify_in_parallel_reports_first_failure() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--jobs", "4", "--format", "json"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains(r#""name":"compFailure""#)
+                .and(predicates::str::contains(r#""name":"testFailure""#).not()),
+        );
+}
+
+#[test]
+fn verify_without_cache() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "verify"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_rustlings_cache_clean() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cache", "clean"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_infinite_loop_times_out() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "infiniteLoop", "--format", "json"])
+        .current_dir("tests/fixture/limits")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(r#""outcome":"timed-out""#));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn run_single_huge_allocation_exceeds_memory() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hugeAllocation", "--format", "json"])
+        .current_dir("tests/fixture/limits")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(r#""outcome":"exceeded-memory""#));
+}
+
+#[test]
+fn run_single_endless_output_exceeds_output() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "endlessOutput"])
+        .current_dir("tests/fixture/limits")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("printed too much output"));
+}
+
+#[test]
+fn run_rustlings_reset_without_original() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "pending_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No original version"));
+}
+
+#[test]
+fn run_rustlings_reset_unchanged_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "finished_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("already in its original state"));
+}
+
+#[test]
+fn run_rustlings_reset_confirmed() {
+    let dir = copy_fixture("state");
+    let exercise = dir.join("finished_exercise.rs");
+    std::fs::write(&exercise, "// mangled beyond repair\n").unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["reset", "finished_exercise"])
+        .current_dir(&dir)
+        .with_stdin()
+        .buffer("y\n")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Reset finished_exercise.rs to its original state!"));
+
+    let restored = std::fs::read_to_string(&exercise).unwrap();
+    let original = std::fs::read_to_string(dir.join("finished_exercise.rs.orig")).unwrap();
+    let backups: Vec<_> = std::fs::read_dir(dir.join(".rustlings-backups"))
+        .unwrap()
+        .flatten()
+        .map(|entry| std::fs::read_to_string(entry.path()).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(restored, original);
+    assert_eq!(backups, ["// mangled beyond repair\n"]);
+}
+
+#[test]
+fn run_rustlings_solution_locked() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "pending_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("unlocks after 1000 failed attempts"));
+}
+
+#[test]
+fn run_rustlings_solution_unlocked() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "finished_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("+    println!(\"solved\");"));
+}
+
+#[test]
+fn run_rustlings_solution_missing() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "pending_test_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("There is no reference solution"));
+}
+
+#[test]
+fn get_hint_up_to_level() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "compFailure", "--level", "2"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(0)
+        .stdout(predicates::str::contains("Hint 1/3: First nudge"))
+        .stdout(predicates::str::contains("Hint 2/3: Stronger nudge"))
+        .stdout(predicates::str::contains("Nearly the answer").not())
+        .stdout(predicates::str::contains("1 more level"));
+}
+
+#[test]
+fn run_rustlings_explain_last_errors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "run", "immutable"])
+        .current_dir("tests/fixture/explain")
+        .assert()
+        .code(1);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["explain", "immutable"])
+        .current_dir("tests/fixture/explain")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("error[E0384]"))
+        .stdout(predicates::str::contains("   |     x = 6;"))
+        .stdout(predicates::str::contains("An immutable variable was reassigned."));
+}
+
+#[test]
+fn run_rustlings_explain_forgets_fixed_errors() {
+    let dir = copy_fixture("explain");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "run", "immutable"])
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    let fixed = std::fs::read_to_string(dir.join("immutable.rs"))
+        .unwrap()
+        .replace("let x", "let mut x");
+    std::fs::write(dir.join("immutable.rs"), fixed).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--no-cache", "run", "immutable"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["explain", "immutable"])
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("no compiler errors"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_explain_unexpected_errors() {
+    let dir = copy_fixture("compile_fail");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "wrongError"])
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["explain", "wrongError"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("error[E0308]"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_rustlings_explain_without_errors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["explain", "finished_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("no compiler errors"));
+}
+
+#[test]
+fn run_compile_fail_with_expected_errors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "movedValue"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("error[E0382]"));
+}
+
+#[test]
+fn run_compile_fail_that_compiles() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compilesCleanly"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("should fail to compile"));
+}
+
+#[test]
+fn run_compile_fail_with_wrong_errors() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "wrongError"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("for the wrong reason"))
+        .stdout(predicates::str::contains("error[E0308]"));
+}
+
+#[test]
+fn verify_compile_fail_json_outcomes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--format", "json"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\"outcome\":\"failed-as-expected\""))
+        .stdout(predicates::str::contains("\"outcome\":\"compiled-unexpectedly\""));
+}
+
+#[test]
+fn run_golden_output_from_stdin() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greet"])
+        .current_dir("tests/fixture/golden")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello, Ferris!"));
+}
+
+#[test]
+fn run_golden_output_mismatch_shows_diff() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "count"])
+        .current_dir("tests/fixture/golden")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("printed the wrong output"))
+        .stdout(predicates::str::contains("-4\n+3\n"));
+}
+
+#[test]
+fn run_golden_output_regex() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "timing"])
+        .current_dir("tests/fixture/golden")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_invalid_output_regex_is_reported() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "shout"])
+        .current_dir("tests/fixture/bad_regex")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The expected_stdout_regex of shout in info.toml is not a valid regex:",
+        ))
+        .stdout(predicates::str::contains("unclosed group"));
+}
+
+#[test]
+fn check_course_reports_invalid_output_regex() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-course")
+        .current_dir("tests/fixture/bad_regex")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("the expected_stdout_regex of shout is not a valid regex:"));
+}
+
+#[test]
+fn run_cargo_exercise_with_vendored_dependency() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hello"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Successfully tested hello"));
+}
+
+#[test]
+fn run_cargo_exercise_compile_failure() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "broken"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("error[E0308]"))
+        .stdout(predicates::str::contains("broken/src/lib.rs:2:5"));
+}
+
+#[test]
+fn run_cargo_exercise_keeps_flags_with_spaces_whole() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "spaced"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_cargo_exercise_times_out_and_stops_its_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "forever"])
+        .current_dir("tests/fixture/cargo")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("forever took too long to run and was stopped!"));
+    let still_running = std::process::Command::new("pgrep")
+        .arg("^forever-")
+        .output()
+        .unwrap();
+    assert!(still_running.stdout.is_empty());
+}
+
+#[test]
+fn run_single_denies_warnings_from_defaults() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "unusedVariable"])
+        .current_dir("tests/fixture/build")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("unused variable"));
+}
+
+#[test]
+fn run_single_overrides_deny_warnings() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "allowedWarning"])
+        .current_dir("tests/fixture/build")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_with_edition() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "oldEdition"])
+        .current_dir("tests/fixture/build")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_with_rustc_flags() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "extraFlags"])
+        .current_dir("tests/fixture/build")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_clippy_denies_listed_lints() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "floatCmp"])
+        .current_dir("tests/fixture/clippy")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("clippy::float-cmp"));
+}
+
+#[test]
+fn run_single_clippy_lint_free() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cleanCode"])
+        .current_dir("tests/fixture/clippy")
+        .assert()
+        .success();
+}
+
+#[test]
+fn check_course_consistent() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-course")
+        .current_dir("tests/fixture/course/clean")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("The course is consistent"));
+}
+
+#[test]
+fn check_course_dotted_paths() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-course")
+        .current_dir("tests/fixture/course/dotted")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("not listed").not());
+}
+
+#[test]
+fn check_course_reports_drift() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-course")
+        .current_dir("tests/fixture/course/drift")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("the name `greeting` is used by more than one exercise"))
+        .stdout(predicates::str::contains("homeworks/basics/gone.rs, which doesn't exist"))
+        .stdout(predicates::str::contains("homeworks/basics/unlisted.rs is not listed in info.toml"))
+        .stdout(predicates::str::contains("homeworks/basics/farewell.rs has no `I AM NOT DONE` marker"))
+        .stdout(predicates::str::contains("homeworks/basics/mod.rs doesn't declare `unlisted`"))
+        .stdout(predicates::str::contains("homeworks/mod.rs declares `ghost`"))
+        .stdout(predicates::str::contains("silent has no hint"))
+        .stdout(predicates::str::contains("homeworks/documented/src/lib.rs has doctests, which are never run"));
+}
+
+#[test]
+fn run_single_with_hidden_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "doubler"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_hidden_tests_catch_special_cases() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "cheater"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("hidden_tests::doubles_negative_numbers ... FAILED"))
+        .stdout(predicates::str::contains("right: -6").not());
+}
+
+#[test]
+fn run_single_hidden_tests_source_not_shown() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "renamed"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("E0425"))
+        .stdout(predicates::str::contains("secret_expected_value").not());
+}
+
+#[test]
+fn verify_accepts_intact_protected_regions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/protected/intact")
+        .assert()
+        .success();
+}
+
+#[test]
+fn verify_refuses_changed_protected_region() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--format", "json"])
+        .current_dir("tests/fixture/protected/tampered")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(r#""outcome":"tampered""#));
+}
+
+#[test]
+fn verify_shows_changes_to_protected_region() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/protected/tampered")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("changes code it isn't allowed to change"))
+        .stdout(predicates::str::contains("+        assert!(!is_even(4));"));
+}
+
+#[test]
+fn grade_scores_exercises_with_penalties() {
+    let output = std::env::temp_dir().join(format!("rustlings_grades_{}.csv", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade", "-o"])
+        .arg(&output)
+        .current_dir("tests/fixture/grade")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Total score: 3/7"));
+    let csv = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    assert!(csv.contains("pending,1,compiled,2,0,false,2\n"));
+    assert!(csv.contains("hinted,1,compiled,1,2,false,0.5\n"));
+    assert!(csv.contains("broken,1,compile-failed,3,0,false,0\n"));
+    assert!(csv.contains("peeked,2,compiled,1,0,true,0.5\n"));
+    assert!(csv.ends_with("total,,,7,,,3\n"));
+}
+
+#[test]
+fn grade_ignores_forged_cache_results() {
+    let dir = copy_fixture("grade");
+    let _ignored = std::fs::remove_dir_all(dir.join(".rustlings-cache"));
+    // Cache the failed compilation of broken, then claim it compiled
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "broken"])
+        .current_dir(&dir)
+        .assert()
+        .code(1);
+    for entry in std::fs::read_dir(dir.join(".rustlings-cache")).unwrap().flatten() {
+        std::fs::write(
+            entry.path().join("result.toml"),
+            "outcome = \"compiled\"\nstdout = \"\"\nstderr = \"\"\n",
+        )
+        .unwrap();
+    }
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade", "-o", "grades.csv"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Total score: 3/7"));
+    let csv = std::fs::read_to_string(dir.join("grades.csv")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(csv.contains("broken,1,compile-failed,3,0,false,0\n"));
+}
+
+#[test]
+fn grade_single_homework_as_json() {
+    let output = std::env::temp_dir().join(format!("rustlings_grades_{}.json", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade", "2", "-o"])
+        .arg(&output)
+        .current_dir("tests/fixture/grade")
+        .assert()
+        .success();
+    let json = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    assert!(json.contains(r#""name": "peeked""#));
+    assert!(!json.contains(r#""name": "hinted""#));
+    assert!(json.contains(r#""max_score": 1.0"#));
+}
+
+#[test]
+fn grade_class_combines_the_grades_of_every_submission() {
+    let csv_path = std::env::temp_dir().join(format!("rustlings_class_{}.csv", std::process::id()));
+    let html_path = csv_path.with_extension("html");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade-class", "submissions", "-o"])
+        .arg(&csv_path)
+        .arg("--html")
+        .arg(&html_path)
+        .current_dir("tests/fixture/class")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Failed to grade dave"));
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    let html = std::fs::read_to_string(&html_path).unwrap();
+    std::fs::remove_file(&csv_path).unwrap();
+    std::fs::remove_file(&html_path).unwrap();
+    assert!(csv.starts_with("student,greeting,sum,score,max_score,progress,error\n"));
+    // alice revealed a hint, as the progress record of the submission says
+    assert!(csv.contains("alice,0.9,1,1.9,2,present,\n"));
+    // The course's info.toml and hidden tests replace the ones bob changed
+    assert!(csv.contains("bob,1,0,1,2,missing,\n"));
+    assert!(csv.contains("carol,1,1,2,2,missing,\n"));
+    assert!(csv.contains("dave,,,,,,failed to unpack the submission"));
+    assert!(html.contains("<td>2/2 (no progress record, so no penalties)</td>"));
+    assert!(html.contains("<p>3 of 4 submissions graded.</p>"));
+    assert!(html.contains("<tr><td>sum</td><td>1/3 (33%)</td>"));
+    assert!(html.find("<td>sum</td>") < html.find("<td>greeting</td>"));
+}
+
+#[test]
+fn grade_class_fails_without_submissions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["grade-class", "instructor"])
+        .current_dir("tests/fixture/class")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("There are no submissions in instructor."));
+}
+
+#[test]
+fn certify_and_verify_certificate() {
+    let path = std::env::temp_dir().join(format!("rustlings_certificate_{}.json", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["certify", "1", "--keypair", "student.json", "-o"])
+        .arg(&path)
+        .current_dir("tests/fixture/certify")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Certified homework 1!"));
+    let certificate = std::fs::read_to_string(&path).unwrap();
+    assert!(certificate.contains(r#""name": "done""#));
+    assert!(certificate.contains(r#""name": "tested""#));
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify-certificate")
+        .arg(&path)
+        .current_dir("tests/fixture/certify")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Homework 1 was completed (2 exercise(s))"));
+
+    // Claiming another homework breaks the signature
+    std::fs::write(&path, certificate.replace(r#""homework": 1"#, r#""homework": 2"#)).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify-certificate")
+        .arg(&path)
+        .current_dir("tests/fixture/certify")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("the signature doesn't match the certificate"))
+        .stdout(predicates::str::contains("it doesn't cover pending.rs"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn certify_refuses_unfinished_homework() {
+    let path = std::env::temp_dir().join(format!("rustlings_unfinished_{}.json", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["certify", "2", "--keypair", "student.json", "-o"])
+        .arg(&path)
+        .current_dir("tests/fixture/certify")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("pending.rs (pending-marker)"));
+    assert!(!path.exists());
+}
+
+#[test]
+fn certify_refuses_inconsistent_keypair() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["certify", "1", "--keypair", "mismatched.json"])
+        .current_dir("tests/fixture/certify")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("its public key doesn't match its secret key"));
+}